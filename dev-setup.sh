@@ -6,8 +6,12 @@
 # Get the directory where this script is located
 SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
 
-# Source the shell function
-source "$SCRIPT_DIR/scripts/p-function.sh"
+# Load the shell function generated by the development binary
+if [ -n "$ZSH_VERSION" ]; then
+    eval "$("$SCRIPT_DIR/target/release/p-bin" shell-init zsh)"
+else
+    eval "$("$SCRIPT_DIR/target/release/p-bin" shell-init bash)"
+fi
 
 echo "✅ Project Man development environment loaded!"
 echo "💡 Using binary: $SCRIPT_DIR/target/release/p-bin"
@@ -24,8 +24,8 @@ main() {
     log_info "🚀 Installing Project Man..."
     
     # Check files exist
-    if [ ! -f "$SCRIPT_DIR/p-bin" ] || [ ! -f "$SCRIPT_DIR/scripts/p-function.sh" ]; then
-        log_error "Required files not found. Ensure you have p-bin and scripts/p-function.sh"
+    if [ ! -f "$SCRIPT_DIR/p-bin" ]; then
+        log_error "Required files not found. Ensure you have p-bin"
         exit 1
     fi
     
@@ -36,7 +36,6 @@ main() {
     # Copy files
     log_info "Copying files..."
     cp "$SCRIPT_DIR/p-bin" "$INSTALL_DIR/"
-    cp "$SCRIPT_DIR/scripts/p-function.sh" "$INSTALL_DIR/"
     chmod +x "$INSTALL_DIR/p-bin"
     
     # Setup shell integration
@@ -44,31 +43,57 @@ main() {
     
     log_success "🎉 Installation complete!"
     echo ""
-    log_info "Restart your terminal or run: source ~/.bashrc (or ~/.zshrc)"
+    log_info "Restart your terminal or reload your shell config"
     log_info "Then try: p init ~/workspace"
 }
 
+# Older versions sourced a hand-written p-function.sh or shell-integration.sh, which no
+# longer exist; comment those lines out so they don't fail in every new shell
+disable_old_shell_function() {
+    local shell_config="$1"
+    local old_line='^([[:space:]]*)((source|\.)[[:space:]].*(p-function|shell-integration)\.sh.*)$'
+    
+    if grep -qE "$old_line" "$shell_config" 2>/dev/null; then
+        sed -i.bak -E "s/$old_line/\\1# \\2  # replaced by p-bin shell-init/" "$shell_config"
+        log_info "Commented out the old shell function in $shell_config (backup in $shell_config.bak)"
+    fi
+    rm -f "$INSTALL_DIR/p-function.sh"
+}
+
 setup_shell_integration() {
     log_info "Setting up shell integration..."
     
+    # The shell function is generated by the binary itself
     local shell_config=""
+    local init_line=""
     case "$SHELL" in
-        */bash) shell_config="$HOME/.bashrc" ;;
-        */zsh) shell_config="$HOME/.zshrc" ;;
+        */bash)
+            shell_config="$HOME/.bashrc"
+            init_line="eval \"\$(\"$INSTALL_DIR/p-bin\" shell-init bash)\""
+            ;;
+        */zsh)
+            shell_config="$HOME/.zshrc"
+            init_line="eval \"\$(\"$INSTALL_DIR/p-bin\" shell-init zsh)\""
+            ;;
+        */fish)
+            shell_config="$HOME/.config/fish/config.fish"
+            init_line="\"$INSTALL_DIR/p-bin\" shell-init fish | source"
+            mkdir -p "$(dirname "$shell_config")"
+            ;;
         *) 
             log_error "Unsupported shell: $SHELL"
-            log_info "Please add this line to your shell config manually:"
-            echo "source \"$INSTALL_DIR/p-function.sh\""
+            log_info "Run '$INSTALL_DIR/p-bin shell-init --help' and load the function for your shell manually"
             return
             ;;
     esac
     
-    # Add shell function source if not already there
-    local source_line="source \"$INSTALL_DIR/p-function.sh\""
-    if ! grep -q "p-function.sh" "$shell_config" 2>/dev/null; then
+    disable_old_shell_function "$shell_config"
+    
+    # Add shell function if not already there
+    if ! grep -q "p-bin\" shell-init" "$shell_config" 2>/dev/null; then
         echo "" >> "$shell_config"
         echo "# Project Man shell function" >> "$shell_config"
-        echo "$source_line" >> "$shell_config"
+        echo "$init_line" >> "$shell_config"
         log_success "Added shell function to $shell_config"
     else
         log_info "Shell function already configured in $shell_config"
@@ -134,9 +134,8 @@ install_binary() {
     # Create install directory  
     mkdir -p "$INSTALL_DIR"
     
-    # Find binary and wrapper script
+    # Find binary
     local binary_path=""
-    local wrapper_path=""
     
     # Look for binary
     if [ -f "$extract_dir/p-bin" ]; then
@@ -145,13 +144,6 @@ install_binary() {
         binary_path=$(find "$extract_dir" -name "p-bin" -type f | head -1)
     fi
     
-    # Look for shell function script
-    if [ -f "$extract_dir/scripts/p-function.sh" ]; then
-        wrapper_path="$extract_dir/scripts/p-function.sh"
-    else
-        wrapper_path=$(find "$extract_dir" -name "p-function.sh" -type f | head -1)
-    fi
-    
     if [ -z "$binary_path" ] || [ ! -f "$binary_path" ]; then
         log_error "Binary p-bin not found in extracted archive"
         log_error "Contents of extract directory:"
@@ -159,46 +151,62 @@ install_binary() {
         exit 1
     fi
     
-    if [ -z "$wrapper_path" ] || [ ! -f "$wrapper_path" ]; then
-        log_error "Shell function script p-function.sh not found in extracted archive"
-        log_error "Contents of extract directory:"
-        ls -la "$extract_dir" >&2
-        log_error "Contents of scripts directory:"
-        ls -la "$extract_dir/scripts" >&2
-        exit 1
-    fi
-    
     # Copy files
     cp "$binary_path" "$INSTALL_DIR/"
-    cp "$wrapper_path" "$INSTALL_DIR/"
     chmod +x "$INSTALL_DIR/p-bin"
     
-    log_success "Binary and wrapper installed to $INSTALL_DIR/" >&2
+    log_success "Binary installed to $INSTALL_DIR/" >&2
 }
 
 
+# Older versions sourced a hand-written p-function.sh or shell-integration.sh, which no
+# longer exist; comment those lines out so they don't fail in every new shell
+disable_old_shell_function() {
+    local shell_config="$1"
+    local old_line='^([[:space:]]*)((source|\.)[[:space:]].*(p-function|shell-integration)\.sh.*)$'
+    
+    if grep -qE "$old_line" "$shell_config" 2>/dev/null; then
+        sed -i.bak -E "s/$old_line/\\1# \\2  # replaced by p-bin shell-init/" "$shell_config"
+        log_info "Commented out the old shell function in $shell_config (backup in $shell_config.bak)" >&2
+    fi
+    rm -f "$INSTALL_DIR/p-function.sh"
+}
+
 # Setup shell integration
 setup_shell_integration() {
     log_info "Setting up shell integration..." >&2
     
+    # The shell function is generated by the binary itself
     local shell_config=""
+    local init_line=""
     case "$SHELL" in
-        */bash) shell_config="$HOME/.bashrc" ;;
-        */zsh) shell_config="$HOME/.zshrc" ;;
+        */bash)
+            shell_config="$HOME/.bashrc"
+            init_line="eval \"\$(\"$INSTALL_DIR/p-bin\" shell-init bash)\""
+            ;;
+        */zsh)
+            shell_config="$HOME/.zshrc"
+            init_line="eval \"\$(\"$INSTALL_DIR/p-bin\" shell-init zsh)\""
+            ;;
+        */fish)
+            shell_config="$HOME/.config/fish/config.fish"
+            init_line="\"$INSTALL_DIR/p-bin\" shell-init fish | source"
+            mkdir -p "$(dirname "$shell_config")"
+            ;;
         *) 
             log_error "Unsupported shell: $SHELL"
-            log_info "Please add this line to your shell config manually:"
-            echo "source \"$INSTALL_DIR/p-function.sh\""
+            log_info "Run '$INSTALL_DIR/p-bin shell-init --help' and load the function for your shell manually"
             return
             ;;
     esac
     
-    # Add shell function source if not already there
-    local source_line="source \"$INSTALL_DIR/p-function.sh\""
-    if ! grep -q "p-function.sh" "$shell_config" 2>/dev/null; then
+    disable_old_shell_function "$shell_config"
+    
+    # Add shell function if not already there
+    if ! grep -q "p-bin\" shell-init" "$shell_config" 2>/dev/null; then
         echo "" >> "$shell_config"
         echo "# Project Man shell function" >> "$shell_config"
-        echo "$source_line" >> "$shell_config"
+        echo "$init_line" >> "$shell_config"
         log_success "Added shell function to $shell_config" >&2
         log_info "Restart your terminal or run: source $shell_config" >&2
     else
@@ -211,17 +219,12 @@ setup_shell_integration() {
 verify_installation() {
     log_info "Verifying installation..." >&2
     
-    # Check if binary and shell function exist
+    # Check if binary exists
     if [ ! -x "$INSTALL_DIR/p-bin" ]; then
         log_error "Binary not found or not executable at $INSTALL_DIR/p-bin"
         return 1
     fi
     
-    if [ ! -f "$INSTALL_DIR/p-function.sh" ]; then
-        log_error "Shell function script not found at $INSTALL_DIR/p-function.sh"
-        return 1
-    fi
-    
     # Test binary execution
     if "$INSTALL_DIR/p-bin" --version >/dev/null 2>&1; then
         log_success "Project Man binary is working correctly" >&2
@@ -273,7 +276,7 @@ main() {
         log_success "🎉 Project Man has been successfully installed!"
         echo ""
         log_info "Next steps:"
-        log_info "1. Restart your terminal or run: source ~/.bashrc (or ~/.zshrc)"
+        log_info "1. Restart your terminal or reload your shell config"
         log_info "2. Initialize a workspace: p init ~/workspace"
         log_info "3. Add repositories: p add rust-lang/rust"
         log_info "4. Navigate: p go rust"
@@ -1,4 +1,10 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use clap_complete::ArgValueCompleter;
+use crate::commands::list::{ListColumn, ListSort};
+use crate::config::RepositoryConfig;
+use crate::git::SyncStrategy;
+use crate::output::OutputFormat;
+use crate::shell::{Shell, complete_config_key, complete_repository, complete_tag};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -6,6 +12,18 @@ use std::path::PathBuf;
 #[command(about = "Project Man - A CLI tool for managing multiple code repositories")]
 #[command(version = "0.1.0")]
 pub struct Cli {
+    #[arg(long, global = true, help = "Use this workspace instead of the active one")]
+    pub workspace: Option<String>,
+    
+    #[arg(long, global = true, conflicts_with = "select", help = "When several repositories match, take the best match without asking")]
+    pub first: bool,
+    
+    #[arg(long, global = true, value_name = "N", value_delimiter = ',', help = "When several repositories match, take the N-th (comma-separated where several can be chosen)")]
+    pub select: Vec<usize>,
+    
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Human, help = "Output format for list, status, sync and grep")]
+    pub format: OutputFormat,
+    
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -16,45 +34,82 @@ pub enum Commands {
     Init {
         #[arg(help = "Path to the workspace directory")]
         path: PathBuf,
+        #[arg(long, default_value = "default", help = "Name of the workspace")]
+        name: String,
+    },
+    
+    #[command(about = "Manage named workspaces")]
+    Workspace {
+        #[command(subcommand)]
+        subcommand: WorkspaceCommands,
     },
     
     #[command(about = "Add a repository to the workspace")]
     Add {
         #[arg(help = "Repository URL or shorthand (e.g., user/repo)")]
         repository: String,
-        #[arg(long, help = "Output directory path for shell integration")]
+        #[arg(long = "tag", value_name = "TAG", help = "Tag the repository (repeatable)")]
+        tags: Vec<String>,
+        #[arg(long, help = "Write the target directory to the file named by $P_CD_FILE (used by the shell integration)")]
         output_cd: bool,
     },
     
     #[command(about = "Navigate to a repository")]
     Go {
-        #[arg(help = "Repository pattern for fuzzy search (empty to go to workspace)")]
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository query, e.g. 'api owner:my-org' (empty to go to workspace)")]
         pattern: Option<String>,
-        #[arg(long, help = "Output directory path for shell integration")]
+        #[command(flatten)]
+        filter: TagFilter,
+        #[arg(short, long, help = "Without a pattern, pick from recently visited repositories")]
+        recent: bool,
+        #[arg(long, help = "Write the target directory to the file named by $P_CD_FILE (used by the shell integration)")]
         output_cd: bool,
     },
     
     #[command(about = "List all repositories")]
-    List,
+    List {
+        #[command(flatten)]
+        filter: TagFilter,
+        #[arg(short, long, help = "One aligned row per repository")]
+        table: bool,
+        #[arg(long, value_enum, value_delimiter = ',', help = "Table columns, comma-separated (default: name,branch,status,last_sync); implies --table")]
+        columns: Vec<ListColumn>,
+        #[arg(long, conflicts_with_all = ["table", "columns"], help = "Print each repository with a format string, e.g. '{path}\\t{branch}'; fields are the column names")]
+        template: Option<String>,
+        #[arg(long, value_enum, default_value_t = ListSort::Name, help = "Order of the repositories")]
+        sort: ListSort,
+        #[arg(short, long, help = "Reverse the order")]
+        reverse: bool,
+    },
     
     #[command(about = "Remove a repository from workspace")]
     Remove {
-        #[arg(help = "Repository pattern to remove")]
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository query to remove; several matches can be removed at once")]
         pattern: String,
     },
     
     #[command(about = "Synchronize repositories (pull updates)")]
     Sync {
-        #[arg(help = "Optional repository pattern to sync")]
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Optional repository query to sync, e.g. 'behind:true'")]
         pattern: Option<String>,
+        #[command(flatten)]
+        filter: TagFilter,
+        #[arg(short, long, help = "Choose which of the matching repositories to sync")]
+        interactive: bool,
+        #[arg(short, long, default_value_t = crate::commands::sync::DEFAULT_JOBS, help = "Number of repositories to sync at the same time")]
+        jobs: usize,
+        #[arg(short, long, value_enum, help = "How to update branches; overrides each repository's own strategy (default: ff-only)")]
+        strategy: Option<SyncStrategy>,
     },
     
     #[command(about = "Search across repositories")]
     Grep {
         #[arg(help = "Search pattern")]
         pattern: String,
-        #[arg(help = "Optional repository pattern to limit search")]
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Optional repository query to limit search")]
         repo_pattern: Option<String>,
+        #[command(flatten)]
+        filter: TagFilter,
     },
     
     #[command(about = "Migrate existing repositories to workspace")]
@@ -70,7 +125,46 @@ pub enum Commands {
     },
     
     #[command(about = "Show workspace status")]
-    Status,
+    Status {
+        #[command(flatten)]
+        filter: TagFilter,
+    },
+    
+    #[command(about = "Manage repository tags")]
+    Tag {
+        #[command(subcommand)]
+        subcommand: TagCommands,
+    },
+    
+    #[command(about = "Manage alternative names used when searching for a repository")]
+    Alias {
+        #[command(subcommand)]
+        subcommand: AliasCommands,
+    },
+    
+    #[command(about = "Set how 'p sync' updates a repository")]
+    Strategy {
+        #[command(subcommand)]
+        subcommand: StrategyCommands,
+    },
+    
+    #[command(about = "Rebuild the workspace registry from repositories on disk")]
+    Reindex {
+        #[arg(long, help = "Drop registry entries whose directory no longer exists")]
+        prune: bool,
+    },
+    
+    #[command(about = "Check the workspace registry against the repositories on disk")]
+    Doctor {
+        #[arg(long, help = "Interactively fix the problems found")]
+        fix: bool,
+    },
+    
+    #[command(about = "Print the shell function that lets p change directory, e.g. eval \"$(p-bin shell-init bash)\"")]
+    ShellInit {
+        #[arg(value_enum, help = "Shell to generate the function for")]
+        shell: Shell,
+    },
 }
 
 #[derive(Subcommand)]
@@ -80,7 +174,7 @@ pub enum ConfigCommands {
     
     #[command(about = "Set a configuration value")]
     Set {
-        #[arg(help = "Configuration key")]
+        #[arg(add = ArgValueCompleter::new(complete_config_key), help = "Configuration key")]
         key: String,
         #[arg(help = "Configuration value")]
         value: String,
@@ -88,7 +182,114 @@ pub enum ConfigCommands {
     
     #[command(about = "Get a configuration value")]
     Get {
-        #[arg(help = "Configuration key")]
+        #[arg(add = ArgValueCompleter::new(complete_config_key), help = "Configuration key")]
         key: String,
     },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommands {
+    #[command(about = "Register a new named workspace")]
+    Add {
+        #[arg(help = "Workspace name")]
+        name: String,
+        #[arg(help = "Path to the workspace directory")]
+        path: PathBuf,
+    },
+    
+    #[command(about = "Make a workspace the active one")]
+    Switch {
+        #[arg(help = "Workspace name")]
+        name: String,
+    },
+    
+    #[command(about = "List configured workspaces")]
+    List,
+    
+    #[command(about = "Forget a workspace (files are kept)")]
+    Remove {
+        #[arg(help = "Workspace name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagCommands {
+    #[command(about = "Add tags to a repository")]
+    Add {
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository pattern")]
+        pattern: String,
+        #[arg(required = true, help = "Tags to add")]
+        tags: Vec<String>,
+    },
+    
+    #[command(about = "Remove tags from a repository")]
+    Remove {
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository pattern")]
+        pattern: String,
+        #[arg(required = true, add = ArgValueCompleter::new(complete_tag), help = "Tags to remove")]
+        tags: Vec<String>,
+    },
+    
+    #[command(about = "List tags and the repositories carrying them")]
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum AliasCommands {
+    #[command(about = "Add an alias to a repository")]
+    Add {
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository pattern")]
+        pattern: String,
+        #[arg(help = "Alias to add")]
+        alias: String,
+    },
+    
+    #[command(about = "Remove an alias from a repository")]
+    Remove {
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository pattern")]
+        pattern: String,
+        #[arg(help = "Alias to remove")]
+        alias: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StrategyCommands {
+    #[command(about = "Use a sync strategy for a repository by default")]
+    Set {
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository pattern")]
+        pattern: String,
+        #[arg(value_enum, help = "Sync strategy")]
+        strategy: SyncStrategy,
+    },
+    
+    #[command(about = "Go back to the default sync strategy (ff-only)")]
+    Unset {
+        #[arg(add = ArgValueCompleter::new(complete_repository), help = "Repository pattern")]
+        pattern: String,
+    },
+}
+
+/// `--tag` / `--exclude-tag` options shared by commands that operate on a set of repositories.
+#[derive(Args, Debug, Default)]
+pub struct TagFilter {
+    #[arg(long = "tag", value_name = "TAG", add = ArgValueCompleter::new(complete_tag), help = "Only repositories with this tag (repeatable, any matches)")]
+    pub tags: Vec<String>,
+    #[arg(long = "exclude-tag", value_name = "TAG", add = ArgValueCompleter::new(complete_tag), help = "Skip repositories with this tag (repeatable)")]
+    pub exclude_tags: Vec<String>,
+}
+
+impl TagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.exclude_tags.is_empty()
+    }
+    
+    pub fn matches(&self, repo_config: &RepositoryConfig) -> bool {
+        let included = self.tags.is_empty()
+            || self.tags.iter().any(|tag| repo_config.has_tag(tag));
+        let excluded = self.exclude_tags.iter().any(|tag| repo_config.has_tag(tag));
+        
+        included && !excluded
+    }
 }
\ No newline at end of file
@@ -1,10 +1,15 @@
-use crate::config::{WorkspaceRegistry, RepositoryConfig, GlobalConfig};
+use crate::config::{WorkspaceRegistry, RepositoryConfig, GlobalConfig, VisitHistory, validate_tag};
 use crate::git::GitManager;
+use crate::shell::hand_off_directory;
 use crate::error::Result;
 
-pub async fn execute(repository: &str, output_cd: bool) -> Result<()> {
+pub async fn execute(repository: &str, tags: &[String], output_cd: bool) -> Result<()> {
+    for tag in tags {
+        validate_tag(tag)?;
+    }
+    
     let git_manager = GitManager::new()?;
-    let mut workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
     let global_config = GlobalConfig::load()?;
     
     // Parse repository URL and get target path
@@ -36,21 +41,26 @@ pub async fn execute(repository: &str, output_cd: bool) -> Result<()> {
     git_manager.clone_repository(&url, &target_path)?;
     
     // Add to workspace registry
-    let repo_config = RepositoryConfig::new(
-        relative_path.clone(),
-        url.clone(),
-        vec![], // No tags by default
-    );
+    let mut repo_config = RepositoryConfig::new(relative_path.clone(), url.clone(), vec![]);
+    for tag in tags {
+        repo_config.add_tag(tag);
+    }
     
-    workspace_registry.add_repository(repo_name.clone(), repo_config);
-    workspace_registry.save()?;
+    WorkspaceRegistry::update(|registry| {
+        registry.add_repository(repo_name.clone(), repo_config);
+        Ok(())
+    })?;
     
     println!("✅ Repository added successfully!");
     println!("📝 Registry updated");
     
     // Output directory for shell integration
     if output_cd {
-        println!("CD_TARGET:{}", target_path.display());
+        hand_off_directory(&target_path)?;
+        
+        if let Err(e) = VisitHistory::record_visit(&repo_name) {
+            eprintln!("⚠️  Failed to record visit: {}", e);
+        }
     }
     
     Ok(())
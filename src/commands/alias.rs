@@ -0,0 +1,53 @@
+use crate::config::{WorkspaceRegistry, validate_alias};
+use crate::search::select_repository;
+use crate::cli::AliasCommands;
+use crate::error::{ProjectManError, Result};
+
+pub async fn execute(subcommand: AliasCommands) -> Result<()> {
+    match subcommand {
+        AliasCommands::Add { pattern, alias } => add_alias(&pattern, &alias).await,
+        AliasCommands::Remove { pattern, alias } => remove_alias(&pattern, &alias).await,
+    }
+}
+
+async fn add_alias(pattern: &str, alias: &str) -> Result<()> {
+    validate_alias(alias)?;
+    
+    let Some(name) = select_repository(pattern)?.map(|result| result.name) else {
+        return Ok(());
+    };
+    
+    let added = WorkspaceRegistry::update(|registry| {
+        let repo = registry.repositories.get_mut(&name)
+            .ok_or_else(|| ProjectManError::RepositoryNotFound(name.clone()))?;
+        Ok(repo.add_alias(alias))
+    })?;
+    
+    if added {
+        println!("✅ {} can now be found as '{}'", name, alias);
+    } else {
+        println!("ℹ️  {} already has alias '{}'", name, alias);
+    }
+    
+    Ok(())
+}
+
+async fn remove_alias(pattern: &str, alias: &str) -> Result<()> {
+    let Some(name) = select_repository(pattern)?.map(|result| result.name) else {
+        return Ok(());
+    };
+    
+    let removed = WorkspaceRegistry::update(|registry| {
+        let repo = registry.repositories.get_mut(&name)
+            .ok_or_else(|| ProjectManError::RepositoryNotFound(name.clone()))?;
+        Ok(repo.remove_alias(alias))
+    })?;
+    
+    if removed {
+        println!("✅ Removed alias '{}' from {}", alias, name);
+    } else {
+        println!("ℹ️  {} has no alias '{}'", name, alias);
+    }
+    
+    Ok(())
+}
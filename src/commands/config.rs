@@ -17,13 +17,18 @@ async fn show_config() -> Result<()> {
     println!();
     
     println!("📁 Workspace:");
-    println!("   path = \"{}\"", config.workspace.path.display());
-    println!("   created_at = \"{}\"", config.workspace.created_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    println!("   active = \"{}\"", config.active_workspace);
+    for (name, workspace) in &config.workspaces {
+        println!("   {}:", name);
+        println!("      path = \"{}\"", workspace.path.display());
+        println!("      created_at = \"{}\"", workspace.created_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
     println!();
     
     println!("🔗 Git:");
     println!("   default_host = \"{}\"", config.git.default_host);
     println!("   default_protocol = \"{}\"", config.git.default_protocol);
+    println!("   backend = \"{}\"", config.git.backend);
     if let Some(ssh_key) = &config.git.ssh_key_path {
         println!("   ssh_key_path = \"{}\"", ssh_key.display());
     } else {
@@ -46,10 +51,7 @@ async fn show_config() -> Result<()> {
 }
 
 async fn set_config(key: &str, value: &str) -> Result<()> {
-    let mut config = GlobalConfig::load()?;
-    
-    config.set_value(key, value)?;
-    config.save()?;
+    GlobalConfig::update(|config| config.set_value(key, value))?;
     
     println!("✅ Configuration updated: {} = \"{}\"", key, value);
     
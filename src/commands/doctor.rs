@@ -0,0 +1,220 @@
+use crate::config::{GlobalConfig, RepositoryConfig, WorkspaceRegistry};
+use crate::git::{GitManager, find_git_repositories};
+use crate::error::Result;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// An index.lock this old is assumed to be left behind by a crashed git process
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10 * 60);
+
+enum Issue {
+    Missing { name: String, repo_config: RepositoryConfig },
+    Unregistered { path: PathBuf, relative_path: String },
+    UrlDrift { name: String, registered: String, actual: String },
+    StaleLock { name: String, lock_path: PathBuf },
+}
+
+pub async fn execute(fix: bool) -> Result<()> {
+    let global_config = GlobalConfig::load()?;
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let git_manager = GitManager::new()?;
+    let workspace_path = global_config.get_workspace_path();
+    
+    println!("🩺 Checking workspace: {}", workspace_path.display());
+    println!();
+    
+    let issues = collect_issues(workspace_path, &workspace_registry, &git_manager)?;
+    
+    if issues.is_empty() {
+        println!("✅ No problems found");
+        return Ok(());
+    }
+    
+    if fix {
+        return fix_issues(issues, workspace_path, &git_manager);
+    }
+    
+    for issue in &issues {
+        print_issue(issue);
+    }
+    println!();
+    println!("📊 Problems found: {}", issues.len());
+    println!("💡 Run 'p doctor --fix' to resolve them interactively");
+    
+    Ok(())
+}
+
+fn collect_issues(workspace_path: &Path, registry: &WorkspaceRegistry, git_manager: &GitManager) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let mut registered_paths = HashSet::new();
+    
+    let mut repositories = registry.list_repositories();
+    repositories.sort_by(|a, b| a.0.cmp(b.0));
+    
+    for (name, repo_config) in repositories {
+        let full_path = workspace_path.join(&repo_config.path);
+        registered_paths.insert(full_path.clone());
+        
+        if !full_path.exists() {
+            issues.push(Issue::Missing { name: name.clone(), repo_config: repo_config.clone() });
+            continue;
+        }
+        
+        if let Ok(Some(actual)) = git_manager.get_remote_url(&full_path) {
+            if actual != repo_config.url {
+                issues.push(Issue::UrlDrift {
+                    name: name.clone(),
+                    registered: repo_config.url.clone(),
+                    actual,
+                });
+            }
+        }
+        
+        let lock_path = full_path.join(".git").join("index.lock");
+        if is_stale(&lock_path) {
+            issues.push(Issue::StaleLock { name: name.clone(), lock_path });
+        }
+    }
+    
+    for path in find_git_repositories(workspace_path)? {
+        if registered_paths.contains(&path) {
+            continue;
+        }
+        
+        let relative_path = path.strip_prefix(workspace_path)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        issues.push(Issue::Unregistered { path, relative_path });
+    }
+    
+    Ok(issues)
+}
+
+fn is_stale(lock_path: &Path) -> bool {
+    std::fs::metadata(lock_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map(|age| age > STALE_LOCK_AGE)
+        .unwrap_or(false)
+}
+
+fn print_issue(issue: &Issue) {
+    match issue {
+        Issue::Missing { name, repo_config } => {
+            println!("❌ {}: directory missing ({})", name, repo_config.path);
+        }
+        Issue::Unregistered { relative_path, .. } => {
+            println!("❓ {}: git repository not in registry", relative_path);
+        }
+        Issue::UrlDrift { name, registered, actual } => {
+            println!("🔀 {}: registry URL differs from origin", name);
+            println!("   registry: {}", registered);
+            println!("   origin:   {}", actual);
+        }
+        Issue::StaleLock { name, lock_path } => {
+            println!("🔒 {}: stale lock file {}", name, lock_path.display());
+        }
+    }
+}
+
+fn fix_issues(issues: Vec<Issue>, workspace_path: &Path, git_manager: &GitManager) -> Result<()> {
+    let total = issues.len();
+    let mut fixed_count = 0;
+    
+    for issue in issues {
+        print_issue(&issue);
+        
+        let fixed = match issue {
+            Issue::Missing { name, repo_config } => {
+                match prompt("   [c]lone again, [f]orget, [s]kip? ")?.as_str() {
+                    "c" => {
+                        git_manager.clone_repository(&repo_config.url, &workspace_path.join(&repo_config.path))?;
+                        true
+                    }
+                    "f" => {
+                        WorkspaceRegistry::update(|registry| {
+                            registry.remove_repository(&name);
+                            Ok(())
+                        })?;
+                        println!("   🗑️  Forgot {}", name);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Issue::Unregistered { path, relative_path } => {
+                match prompt("   [r]egister, [s]kip? ")?.as_str() {
+                    "r" => register(&path, &relative_path, git_manager)?,
+                    _ => false,
+                }
+            }
+            Issue::UrlDrift { name, actual, .. } => {
+                match prompt("   [u]pdate registry to origin, [s]kip? ")?.as_str() {
+                    "u" => {
+                        WorkspaceRegistry::update(|registry| {
+                            if let Some(repo) = registry.repositories.get_mut(&name) {
+                                repo.url = actual.clone();
+                            }
+                            Ok(())
+                        })?;
+                        println!("   ✅ Registry URL updated");
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Issue::StaleLock { lock_path, .. } => {
+                match prompt("   [d]elete lock file, [s]kip? ")?.as_str() {
+                    "d" => {
+                        std::fs::remove_file(&lock_path)?;
+                        println!("   🗑️  Lock file deleted");
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        };
+        
+        if fixed {
+            fixed_count += 1;
+        }
+    }
+    
+    println!();
+    println!("📊 Problems fixed: {} of {}", fixed_count, total);
+    
+    Ok(())
+}
+
+fn register(path: &Path, relative_path: &str, git_manager: &GitManager) -> Result<bool> {
+    let Some(url) = git_manager.get_remote_url(path)? else {
+        println!("   ⚠️  No origin remote, cannot register");
+        return Ok(false);
+    };
+    
+    let repo_config = RepositoryConfig::new(relative_path.to_string(), url, vec![]);
+    
+    // Another path may already hold the derived name, e.g. `a/b_c` and `a_b/c`
+    let repo_name = WorkspaceRegistry::update(|registry| {
+        let repo_name = registry.unique_name(&relative_path.replace("/", "_"));
+        registry.add_repository(repo_name.clone(), repo_config);
+        Ok(repo_name)
+    })?;
+    println!("   ✅ Registered as {}", repo_name);
+    
+    Ok(true)
+}
+
+fn prompt(question: &str) -> Result<String> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    
+    Ok(input.trim().to_lowercase())
+}
\ No newline at end of file
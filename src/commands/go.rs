@@ -1,13 +1,16 @@
-use crate::config::{WorkspaceRegistry, GlobalConfig};
-use crate::search::FuzzySearch;
+use crate::config::{WorkspaceRegistry, GlobalConfig, VisitHistory};
+use crate::search::{FuzzySearch, Query, git_status_of};
+use crate::git::GitManager;
+use crate::shell::hand_off_directory;
+use crate::cli::TagFilter;
 use crate::error::Result;
 
-pub async fn execute(pattern: Option<&str>, output_cd: bool) -> Result<()> {
+pub async fn execute(pattern: Option<&str>, filter: &TagFilter, recent: bool, output_cd: bool) -> Result<()> {
     let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let global_config = GlobalConfig::load()?;
     
-    // If no pattern provided, go to workspace root
-    if pattern.is_none() {
-        let global_config = GlobalConfig::load()?;
+    // If no pattern, tag filter or --recent provided, go to workspace root
+    if pattern.is_none() && filter.is_empty() && !recent {
         let workspace_path = global_config.get_workspace_path();
         
         if !workspace_path.exists() {
@@ -16,15 +19,14 @@ pub async fn execute(pattern: Option<&str>, output_cd: bool) -> Result<()> {
         }
         
         if output_cd {
-            println!("CD_TARGET:{}", workspace_path.display());
+            hand_off_directory(workspace_path)?;
         } else {
             println!("📁 Workspace: {}", workspace_path.display());
         }
         return Ok(());
     }
     
-    let pattern = pattern.unwrap();
-    let fuzzy_search = FuzzySearch::new();
+    let fuzzy_search = FuzzySearch::new()?;
     
     let repositories = workspace_registry.list_repositories();
     
@@ -37,14 +39,22 @@ pub async fn execute(pattern: Option<&str>, output_cd: bool) -> Result<()> {
     // Convert to owned data for search
     let owned_repos: Vec<(String, _)> = repositories
         .into_iter()
+        .filter(|(_, config)| filter.matches(config))
         .map(|(name, config)| (name.clone(), config.clone()))
         .collect();
     
-    // Perform fuzzy search
-    let results = fuzzy_search.search(&owned_repos, pattern);
+    // Perform the search; without a pattern every repository passing the filter is offered,
+    // most visited first
+    let git_manager = GitManager::new()?;
+    let query = Query::parse(pattern.unwrap_or_default())?;
+    let status_of = git_status_of(&git_manager, global_config.get_workspace_path());
+    let results = fuzzy_search.search_query(&owned_repos, &query, status_of);
     
     if results.is_empty() {
-        println!("❌ No repositories found matching '{}'", pattern);
+        match pattern {
+            Some(pattern) => println!("❌ No repositories found matching '{}'", pattern),
+            None => println!("❌ No repositories match the filter."),
+        }
         return Ok(());
     }
     
@@ -60,12 +70,18 @@ pub async fn execute(pattern: Option<&str>, output_cd: bool) -> Result<()> {
         
         if !full_path.exists() {
             println!("❌ Repository directory does not exist: {}", full_path.display());
+            println!("💡 Use 'p doctor --fix' to re-clone or forget it");
             return Ok(());
         }
         
         if output_cd {
             // Output for shell integration
-            println!("CD_TARGET:{}", full_path.display());
+            hand_off_directory(&full_path)?;
+            
+            // Only jumps count; looking a repository up doesn't raise its frecency
+            if let Err(e) = VisitHistory::record_visit(&selected_repo.name) {
+                eprintln!("⚠️  Failed to record visit: {}", e);
+            }
         } else {
             println!("📁 Repository: {}", selected_repo.name);
             println!("📍 Path: {}", full_path.display());
@@ -1,29 +1,46 @@
-use crate::config::WorkspaceRegistry;
-use crate::search::FuzzySearch;
-use crate::error::Result;
+use crate::config::{GlobalConfig, WorkspaceRegistry};
+use crate::git::GitManager;
+use crate::search::{FuzzySearch, Query, git_status_of};
+use crate::cli::TagFilter;
+use crate::error::{ProjectManError, Result};
+use crate::output::{self, GrepHit};
+use std::path::Path;
 use std::process::Command;
 
-pub async fn execute(pattern: &str, repo_pattern: Option<&str>) -> Result<()> {
+pub async fn execute(pattern: &str, repo_pattern: Option<&str>, filter: &TagFilter) -> Result<()> {
+    let global_config = GlobalConfig::load()?;
     let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
     
-    let repositories = workspace_registry.list_repositories();
+    let repositories: Vec<_> = workspace_registry.list_repositories()
+        .into_iter()
+        .filter(|(_, config)| filter.matches(config))
+        .collect();
     
     if repositories.is_empty() {
+        if output::is_machine() {
+            return output::emit::<GrepHit>(&[]);
+        }
         println!("📋 No repositories found in workspace.");
         return Ok(());
     }
     
     let repos_to_search: Vec<(String, _)> = if let Some(repo_pattern) = repo_pattern {
         // Filter repositories by pattern
-        let fuzzy_search = FuzzySearch::new();
+        let fuzzy_search = FuzzySearch::new()?;
         let owned_repos: Vec<(String, _)> = repositories
             .into_iter()
             .map(|(name, config)| (name.clone(), config.clone()))
             .collect();
         
-        let results = fuzzy_search.search(&owned_repos, repo_pattern);
+        let git_manager = GitManager::new()?;
+        let query = Query::parse(repo_pattern)?;
+        let status_of = git_status_of(&git_manager, global_config.get_workspace_path());
+        let results = fuzzy_search.search_query(&owned_repos, &query, status_of);
         
         if results.is_empty() {
+            if output::is_machine() {
+                return output::emit::<GrepHit>(&[]);
+            }
             println!("❌ No repositories found matching '{}'", repo_pattern);
             return Ok(());
         }
@@ -38,6 +55,22 @@ pub async fn execute(pattern: &str, repo_pattern: Option<&str>) -> Result<()> {
             .collect()
     };
     
+    if output::is_machine() {
+        let mut hits = Vec::new();
+        for (name, repo_config) in repos_to_search {
+            let full_path = workspace_registry.get_full_path(&repo_config)?;
+            if !full_path.exists() {
+                eprintln!("⚠️  Skipping {} (directory not found)", name);
+                continue;
+            }
+            match collect_hits(pattern, &full_path, &name) {
+                Ok(repo_hits) => hits.extend(repo_hits),
+                Err(e) => eprintln!("⚠️  Error searching in {}: {}", name, e),
+            }
+        }
+        return output::emit(&hits);
+    }
+    
     println!("🔍 Searching for '{}' in {} repositories...", pattern, repos_to_search.len());
     println!();
     
@@ -164,4 +197,72 @@ fn search_with_grep(pattern: &str, path: &std::path::Path, repo_name: &str) -> R
     } else {
         Ok(0)
     }
+}
+
+/// Matching lines as records, using the same tool and matching rules as the human output.
+fn collect_hits(pattern: &str, path: &Path, repo_name: &str) -> Result<Vec<GrepHit>> {
+    // Both tools put a NUL after the file name, so names containing ':' still parse
+    let (tool, output) = if which::which("rg").is_ok() {
+        ("rg", Command::new("rg")
+            .arg("--color=never")
+            .arg("--no-heading")
+            .arg("--with-filename")
+            .arg("--line-number")
+            .arg("--null")
+            .arg("--smart-case")
+            .arg("--no-ignore")
+            .arg(pattern)
+            .arg(path)
+            .output()?)
+    } else if which::which("grep").is_ok() {
+        ("grep", Command::new("grep")
+            .arg("-r")
+            .arg("-n")
+            .arg("-Z")
+            .arg("--color=never")
+            .arg("-i")
+            .arg(pattern)
+            .arg(path)
+            .output()?)
+    } else {
+        return Err(ProjectManError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Neither 'rg' (ripgrep) nor 'grep' found in PATH",
+        )));
+    };
+    
+    // Both exit with 1 when nothing matched and 2 on errors such as a bad pattern
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        return Err(search_failure(tool, &output));
+    }
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let hits = stdout
+        .lines()
+        .filter_map(|line| {
+            let (file, rest) = line.split_once('\0')?;
+            let (line_number, text) = rest.split_once(':')?;
+            let file = Path::new(file).strip_prefix(path).unwrap_or(Path::new(file));
+            Some(GrepHit {
+                repository: repo_name.to_string(),
+                file: file.to_string_lossy().into_owned(),
+                line: line_number.parse().ok()?,
+                text: text.to_string(),
+            })
+        })
+        .collect();
+    
+    Ok(hits)
+}
+
+/// Error for a failed search, keeping the tool's last line of stderr (which names the tool).
+fn search_failure(tool: &str, output: &std::process::Output) -> ProjectManError {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr.lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} failed ({})", tool, output.status));
+    
+    ProjectManError::Io(std::io::Error::other(reason))
 }
\ No newline at end of file
@@ -2,26 +2,39 @@ use std::path::Path;
 use crate::config::{GlobalConfig, WorkspaceRegistry};
 use crate::error::Result;
 
-pub async fn execute(workspace_path: &Path) -> Result<()> {
+pub async fn execute(workspace_path: &Path, name: &str) -> Result<()> {
+    // Create the workspace directory if it doesn't exist
+    std::fs::create_dir_all(workspace_path)?;
+    
     // Convert to absolute path
     let workspace_path = workspace_path.canonicalize()
         .unwrap_or_else(|_| workspace_path.to_path_buf());
     
-    // Create the workspace directory if it doesn't exist
-    std::fs::create_dir_all(&workspace_path)?;
-    
-    // Create global configuration
-    let global_config = GlobalConfig::new(workspace_path.clone());
-    global_config.save()?;
+    // Create or extend global configuration, keeping other workspaces intact
+    if GlobalConfig::exists()? {
+        GlobalConfig::update(|config| {
+            if let Some(existing) = config.workspaces.get_mut(name) {
+                existing.path = workspace_path.clone();
+            } else {
+                config.add_workspace(name, workspace_path.clone())?;
+            }
+            config.switch_workspace(name)
+        })?;
+    } else {
+        GlobalConfig::new(name, workspace_path.clone()).save()?;
+    }
     
-    // Create workspace registry
-    let mut workspace_registry = WorkspaceRegistry::new();
-    workspace_registry.save()?;
+    // Create workspace registry unless one is already there
+    if !workspace_path.join("project-man.yml").exists() {
+        let mut workspace_registry = WorkspaceRegistry::new();
+        workspace_registry.save()?;
+    }
     
     println!("✅ Workspace initialized successfully!");
+    println!("🏷️  Workspace name: {}", name);
     println!("📁 Workspace path: {}", workspace_path.display());
     println!("⚙️  Global config: ~/.config/project-man/config.toml");
     println!("📋 Workspace registry: {}/project-man.yml", workspace_path.display());
     
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,95 +1,341 @@
-use crate::config::WorkspaceRegistry;
-use crate::git::GitManager;
-use crate::error::Result;
-use chrono::{DateTime, Utc};
+use crate::config::{GlobalConfig, RepositoryConfig, WorkspaceRegistry};
+use crate::git::{GitManager, RepoStatus};
+use crate::cli::TagFilter;
+use crate::error::{ProjectManError, Result};
+use crate::output::{self, RepositoryRecord, describe_branch, describe_status, format_relative_time};
+use clap::ValueEnum;
+use std::path::PathBuf;
 
-pub async fn execute() -> Result<()> {
+/// Columns of `p list --table`, also the placeholders of `--template`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum ListColumn {
+    Name,
+    Path,
+    Branch,
+    Status,
+    LastSync,
+    Tags,
+    Url,
+}
+
+/// Columns shown by `--table` without `--columns`.
+pub const DEFAULT_COLUMNS: [ListColumn; 4] = [ListColumn::Name, ListColumn::Branch, ListColumn::Status, ListColumn::LastSync];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum ListSort {
+    #[default]
+    Name,
+    /// Least recently synced first, never synced before that
+    LastSync,
+    /// Oldest first
+    AddedAt,
+    /// Repositories needing attention first: missing, conflicted, with changes, out of sync, clean
+    Status,
+}
+
+struct Entry<'a> {
+    name: &'a str,
+    repo_config: &'a RepositoryConfig,
+    path: PathBuf,
+    status: Option<RepoStatus>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Text(String),
+    Field(ListColumn),
+}
+
+pub async fn execute(filter: &TagFilter, columns: &[ListColumn], table: bool, template: Option<&str>, sort: ListSort, reverse: bool) -> Result<()> {
     let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let global_config = GlobalConfig::load()?;
     let git_manager = GitManager::new()?;
     
-    let repositories = workspace_registry.list_repositories();
+    // Parse the template first so a typo fails before any git work
+    let template = template.map(parse_template).transpose()?;
+    let table = table || !columns.is_empty();
+    let columns = if columns.is_empty() { &DEFAULT_COLUMNS[..] } else { columns };
     
-    if repositories.is_empty() {
-        println!("📋 No repositories found in workspace.");
-        println!("💡 Use 'p add <repository>' to add repositories.");
-        return Ok(());
+    // Asking git is the slow part; a template like '{path}' or a table without branch and
+    // status doesn't need it
+    let wants_status = |column: &ListColumn| matches!(column, ListColumn::Branch | ListColumn::Status);
+    let needs_status = output::is_machine() || sort == ListSort::Status || match &template {
+        Some(segments) => segments.iter().any(|segment| matches!(segment, Segment::Field(column) if wants_status(column))),
+        None if table => columns.iter().any(wants_status),
+        None => true,
+    };
+    
+    let mut entries = workspace_registry.list_repositories()
+        .into_iter()
+        .filter(|(_, config)| filter.matches(config))
+        .map(|(name, repo_config)| {
+            let path = workspace_registry.get_full_path(repo_config)?;
+            let status = if needs_status && path.exists() {
+                git_manager.get_repository_status(&path).ok()
+            } else {
+                None
+            };
+            Ok(Entry { name, repo_config, path, status })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    
+    entries.sort_by(|a, b| {
+        let by_key = match sort {
+            ListSort::Name => std::cmp::Ordering::Equal,
+            ListSort::LastSync => a.repo_config.last_sync.cmp(&b.repo_config.last_sync),
+            ListSort::AddedAt => a.repo_config.added_at.cmp(&b.repo_config.added_at),
+            ListSort::Status => attention(a).cmp(&attention(b)),
+        };
+        by_key.then_with(|| a.name.cmp(b.name))
+    });
+    if reverse {
+        entries.reverse();
     }
     
-    println!("📋 Repositories in workspace:");
-    println!();
+    if output::is_machine() {
+        let records: Vec<_> = entries.into_iter()
+            .map(|entry| RepositoryRecord::new(entry.name, entry.repo_config, entry.path, entry.status.as_ref()))
+            .collect();
+        return output::emit(&records);
+    }
     
-    for (name, repo_config) in &repositories {
-        let full_path = workspace_registry.get_full_path(repo_config)?;
-        
-        // Get repository status
-        let status = if full_path.exists() {
-            match git_manager.get_repository_status(&full_path) {
-                Ok(status) => {
-                    let mut status_parts = vec![];
-                    
-                    if !status.is_clean {
-                        status_parts.push("dirty".to_string());
-                    }
-                    
-                    if status.ahead > 0 {
-                        status_parts.push(format!("ahead {}", status.ahead));
-                    }
-                    
-                    if status.behind > 0 {
-                        status_parts.push(format!("behind {}", status.behind));
-                    }
-                    
-                    if status_parts.is_empty() {
-                        "clean".to_string()
-                    } else {
-                        status_parts.join(", ")
-                    }
-                }
-                Err(_) => "unknown".to_string(),
-            }
+    if let Some(segments) = template {
+        let lines: Vec<String> = entries.iter()
+            .map(|entry| segments.iter()
+                .map(|segment| match segment {
+                    Segment::Text(text) => text.clone(),
+                    Segment::Field(column) => cell(entry, *column),
+                })
+                .collect())
+            .collect();
+        if !lines.is_empty() {
+            output::page(&lines.join("\n"), &global_config.ui.pager)?;
+        }
+        return Ok(());
+    }
+    
+    if entries.is_empty() {
+        if filter.is_empty() {
+            println!("📋 No repositories found in workspace.");
+            println!("💡 Use 'p add <repository>' to add repositories.");
         } else {
-            "missing".to_string()
-        };
-        
+            println!("📋 No repositories match the tag filter.");
+        }
+        return Ok(());
+    }
+    
+    let lines = if table {
+        render_table(&entries, columns)
+    } else {
+        render_blocks(&entries)
+    };
+    output::page(&lines.join("\n"), &global_config.ui.pager)
+}
+
+fn render_blocks(entries: &[Entry]) -> Vec<String> {
+    let mut lines = vec!["📋 Repositories in workspace:".to_string(), String::new()];
+    
+    for entry in entries {
         // Format last sync time
-        let last_sync = repo_config.last_sync
-            .map(|dt| format_relative_time(dt))
+        let last_sync = entry.repo_config.last_sync
+            .map(format_relative_time)
             .unwrap_or_else(|| "never".to_string());
         
         // Display repository info
-        println!("🔷 {}", name);
-        println!("   📁 {}", full_path.display());
-        println!("   🔗 {}", repo_config.url);
-        println!("   📊 Status: {}", status);
-        println!("   🔄 Last sync: {}", last_sync);
+        lines.push(format!("🔷 {}", entry.name));
+        lines.push(format!("   📁 {}", entry.path.display()));
+        lines.push(format!("   🔗 {}", entry.repo_config.url));
+        match &entry.status {
+            Some(status) => {
+                lines.push(format!("   🌿 Branch: {}", describe_branch(status)));
+                lines.push(format!("   📊 Status: {}", describe_status(status)));
+                if let Some(commit) = &status.last_commit {
+                    lines.push(format!("   📝 Last commit: {} · {}", format_relative_time(commit.date), commit.subject));
+                }
+            }
+            None if entry.path.exists() => lines.push("   📊 Status: unknown".to_string()),
+            None => lines.push("   📊 Status: missing".to_string()),
+        }
+        lines.push(format!("   🔄 Last sync: {}", last_sync));
         
-        if !repo_config.tags.is_empty() {
-            println!("   🏷️  Tags: {}", repo_config.tags.join(", "));
+        if !entry.repo_config.tags.is_empty() {
+            lines.push(format!("   🏷️  Tags: {}", entry.repo_config.tags.join(", ")));
         }
         
-        println!();
+        lines.push(String::new());
     }
     
-    println!("📊 Total repositories: {}", repositories.len());
+    lines.push(format!("📊 Total repositories: {}", entries.len()));
+    lines
+}
+
+/// Aligned columns under an upper-case header; the last column isn't padded.
+fn render_table(entries: &[Entry], columns: &[ListColumn]) -> Vec<String> {
+    let header: Vec<String> = columns.iter()
+        .map(|column| column_name(*column).replace('_', " ").to_uppercase())
+        .collect();
+    let rows: Vec<Vec<String>> = entries.iter()
+        .map(|entry| columns.iter().map(|column| cell(entry, *column)).collect())
+        .collect();
     
-    Ok(())
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|index| std::iter::once(&header)
+            .chain(&rows)
+            .map(|row| row[index].chars().count())
+            .max()
+            .unwrap_or(0))
+        .collect();
+    
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line: Vec<String> = row.iter()
+                .zip(&widths)
+                .map(|(text, width)| format!("{:<width$}", text, width = width))
+                .collect();
+            line.join("  ").trim_end().to_string()
+        })
+        .collect()
 }
 
-fn format_relative_time(dt: DateTime<Utc>) -> String {
-    let now = Utc::now();
-    let duration = now.signed_duration_since(dt);
-    
-    if duration.num_seconds() < 60 {
-        "just now".to_string()
-    } else if duration.num_minutes() < 60 {
-        format!("{} minutes ago", duration.num_minutes())
-    } else if duration.num_hours() < 24 {
-        format!("{} hours ago", duration.num_hours())
-    } else if duration.num_days() < 7 {
-        format!("{} days ago", duration.num_days())
-    } else if duration.num_weeks() < 4 {
-        format!("{} weeks ago", duration.num_weeks())
-    } else {
-        format!("{} months ago", duration.num_days() / 30)
+/// The text a column shows for a repository, in the table and in templates.
+fn cell(entry: &Entry, column: ListColumn) -> String {
+    match column {
+        ListColumn::Name => entry.name.to_string(),
+        ListColumn::Path => entry.path.display().to_string(),
+        ListColumn::Branch => match &entry.status {
+            Some(status) => match &status.branch {
+                Some(branch) => branch.clone(),
+                None => describe_branch(status),
+            },
+            None => String::new(),
+        },
+        ListColumn::Status => match &entry.status {
+            Some(status) => describe_status(status),
+            None if entry.path.exists() => "unknown".to_string(),
+            None => "missing".to_string(),
+        },
+        ListColumn::LastSync => entry.repo_config.last_sync
+            .map(format_relative_time)
+            .unwrap_or_else(|| "never".to_string()),
+        ListColumn::Tags => entry.repo_config.tags.join(","),
+        ListColumn::Url => entry.repo_config.url.clone(),
+    }
+}
+
+fn column_name(column: ListColumn) -> String {
+    column.to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Sort rank for `--sort status`; lower needs attention sooner.
+fn attention(entry: &Entry) -> u8 {
+    match &entry.status {
+        None => 0,
+        Some(status) if status.conflicted > 0 => 1,
+        Some(status) if !status.is_clean() => 2,
+        Some(status) if status.ahead > 0 || status.behind > 0 => 3,
+        Some(_) => 4,
+    }
+}
+
+/// Split a `--template` such as `{path}\t{branch}` into text and placeholders. `\t`, `\n`
+/// and `\\` are unescaped; `{{` and `}}` stand for literal braces.
+fn parse_template(template: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let unescaped = match chars.peek() {
+                    Some('t') => Some('\t'),
+                    Some('n') => Some('\n'),
+                    Some('\\') => Some('\\'),
+                    _ => None,
+                };
+                match unescaped {
+                    Some(unescaped) => {
+                        chars.next();
+                        text.push(unescaped);
+                    }
+                    None => text.push('\\'),
+                }
+            }
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                text.push(c);
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(ProjectManError::Config(format!(
+                            "Unclosed '{{' in template '{}'; write '{{{{' for a literal brace",
+                            template
+                        ))),
+                    }
+                }
+                let column = ListColumn::from_str(name.trim(), true).map_err(|_| {
+                    let known: Vec<String> = ListColumn::value_variants().iter().map(|column| column_name(*column)).collect();
+                    ProjectManError::Config(format!("Unknown template field '{{{}}}'. Available: {}", name, known.join(", ")))
+                })?;
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Field(column));
+            }
+            '}' => return Err(ProjectManError::Config(format!(
+                "Unmatched '}}' in template '{}'; write '}}}}' for a literal brace",
+                template
+            ))),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn text(text: &str) -> Segment {
+        Segment::Text(text.to_string())
+    }
+    
+    #[test]
+    fn parses_fields_and_escapes() {
+        let segments = parse_template(r"{path}\t{branch}\n").unwrap();
+        assert_eq!(segments, vec![
+            Segment::Field(ListColumn::Path),
+            text("\t"),
+            Segment::Field(ListColumn::Branch),
+            text("\n"),
+        ]);
+        
+        assert_eq!(parse_template("{ last_sync }").unwrap(), vec![Segment::Field(ListColumn::LastSync)]);
+        assert_eq!(parse_template(r"a\\b \x").unwrap(), vec![text(r"a\b \x")]);
+    }
+    
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(parse_template("{{{name}}}").unwrap(), vec![text("{"), Segment::Field(ListColumn::Name), text("}")]);
+    }
+    
+    #[test]
+    fn rejects_unknown_and_unclosed_fields() {
+        for template in ["{nam}", "{}", "{path", "path}", "{name} }", "{"] {
+            assert!(matches!(parse_template(template), Err(ProjectManError::Config(_))), "{:?}", template);
+        }
     }
 }
\ No newline at end of file
@@ -1,11 +1,11 @@
 use crate::config::{WorkspaceRegistry, RepositoryConfig, GlobalConfig};
-use crate::git::GitManager;
+use crate::git::{GitManager, find_git_repositories};
 use crate::error::Result;
 use std::path::Path;
 use std::fs;
 
 pub async fn execute(source_path: &Path) -> Result<()> {
-    let mut workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
     let global_config = GlobalConfig::load()?;
     let git_manager = GitManager::new()?;
     
@@ -42,7 +42,7 @@ pub async fn execute(source_path: &Path) -> Result<()> {
     }
     
     let workspace_path = global_config.get_workspace_path();
-    let mut migrated_count = 0;
+    let mut migrated = Vec::new();
     let mut skipped_count = 0;
     
     for repo_path in repositories {
@@ -102,12 +102,17 @@ pub async fn execute(source_path: &Path) -> Result<()> {
             vec!["migrated".to_string()],
         );
         
-        workspace_registry.add_repository(repo_name, repo_config);
-        migrated_count += 1;
+        migrated.push((repo_name, repo_config));
     }
     
     // Save registry
-    workspace_registry.save()?;
+    let migrated_count = migrated.len();
+    WorkspaceRegistry::update(|registry| {
+        for (repo_name, repo_config) in migrated {
+            registry.add_repository(repo_name, repo_config);
+        }
+        Ok(())
+    })?;
     
     println!();
     println!("📊 Migration Summary:");
@@ -118,54 +123,10 @@ pub async fn execute(source_path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn find_git_repositories(dir: &Path) -> Result<Vec<std::path::PathBuf>> {
-    let mut repositories = Vec::new();
-    
-    fn scan_directory(dir: &Path, repositories: &mut Vec<std::path::PathBuf>) -> Result<()> {
-        if dir.join(".git").exists() {
-            repositories.push(dir.to_path_buf());
-            return Ok(()); // Don't recurse into git repos
-        }
-        
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_dir() && !path.file_name().unwrap_or_default().to_string_lossy().starts_with('.') {
-                        let _ = scan_directory(&path, repositories);
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
-    scan_directory(dir, &mut repositories)?;
-    Ok(repositories)
-}
-
 fn analyze_repository(repo_path: &Path, git_manager: &GitManager) -> Result<(String, String, String)> {
-    use std::process::Command;
-    
-    // Get remote origin URL using git command
-    let output = Command::new("git")
-        .arg("remote")
-        .arg("get-url")
-        .arg("origin")
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| crate::error::ProjectManError::Git(format!("Failed to get remote URL: {}", e)))?;
-    
-    if !output.status.success() {
-        return Err(crate::error::ProjectManError::Git("No origin remote found".to_string()));
-    }
-    
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    
-    if url.is_empty() {
-        return Err(crate::error::ProjectManError::Git("Origin URL not found".to_string()));
-    }
+    // Get remote origin URL
+    let url = git_manager.get_remote_url(repo_path)?
+        .ok_or_else(|| crate::error::ProjectManError::Git("No origin remote found".to_string()))?;
     
     // Parse URL to get target path
     let (_, target_path) = git_manager.parse_repository_url(&url)?;
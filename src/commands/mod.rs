@@ -8,5 +8,12 @@ pub mod grep;
 pub mod migrate;
 pub mod config;
 pub mod status;
+pub mod doctor;
+pub mod reindex;
+pub mod tag;
+pub mod alias;
+pub mod strategy;
+pub mod workspace;
+pub mod shell_init;
 
 pub use crate::cli::Commands;
\ No newline at end of file
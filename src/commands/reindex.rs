@@ -0,0 +1,96 @@
+use crate::config::{GlobalConfig, RepositoryConfig, WorkspaceRegistry};
+use crate::git::{GitManager, find_git_repositories};
+use crate::error::Result;
+
+pub async fn execute(prune: bool) -> Result<()> {
+    let global_config = GlobalConfig::load()?;
+    let git_manager = GitManager::new()?;
+    let workspace_path = global_config.get_workspace_path();
+    
+    println!("🔍 Scanning for Git repositories in: {}", workspace_path.display());
+    
+    // Read origins before taking the registry lock; this is the slow part
+    let mut found = Vec::new();
+    for repo_path in find_git_repositories(workspace_path)? {
+        let relative_path = repo_path.strip_prefix(workspace_path)
+            .unwrap_or(&repo_path)
+            .to_string_lossy()
+            .to_string();
+        
+        match git_manager.get_remote_url(&repo_path)? {
+            Some(url) => found.push((relative_path, url)),
+            None => println!("   ⚠️  Skipping {} (no origin remote)", relative_path),
+        }
+    }
+    
+    found.sort();
+    
+    println!("📦 Found {} Git repositories", found.len());
+    println!();
+    
+    let (added, updated, kept, pruned) = WorkspaceRegistry::rebuild(|registry| {
+        let (mut added, mut updated, mut kept) = (0, 0, 0);
+        
+        for (relative_path, url) in &found {
+            // Match on location, so entries keep their name, tags, added_at and last_sync
+            let existing = registry.repositories
+                .iter_mut()
+                .find(|(_, repo)| repo.path == *relative_path);
+            
+            match existing {
+                Some((name, repo)) if repo.url != *url => {
+                    println!("🔄 {}: URL updated to {}", name, url);
+                    repo.url = url.clone();
+                    updated += 1;
+                }
+                Some(_) => kept += 1,
+                None => {
+                    // The derived name may belong to another entry, e.g. `a/b_c` and `a_b/c`;
+                    // never replace that one
+                    let derived_name = relative_path.replace("/", "_");
+                    let repo_name = registry.unique_name(&derived_name);
+                    match registry.repositories.get(&derived_name) {
+                        Some(other) => println!("➕ {}: added ({} is already {})", repo_name, derived_name, other.path),
+                        None => println!("➕ {}: added", repo_name),
+                    }
+                    registry.add_repository(
+                        repo_name,
+                        RepositoryConfig::new(relative_path.clone(), url.clone(), vec![]),
+                    );
+                    added += 1;
+                }
+            }
+        }
+        
+        // Entries without a directory are kept unless asked otherwise, see `p doctor`
+        let missing: Vec<String> = registry.repositories
+            .iter()
+            .filter(|(_, repo)| !found.iter().any(|(path, _)| *path == repo.path))
+            .map(|(name, _)| name.clone())
+            .collect();
+        
+        for name in &missing {
+            if prune {
+                registry.remove_repository(name);
+                println!("🗑️  {}: removed (not found on disk)", name);
+            } else {
+                println!("⚠️  {}: not found on disk, kept", name);
+            }
+        }
+        
+        let pruned = if prune { missing.len() } else { 0 };
+        Ok((added, updated, kept, pruned))
+    })?;
+    
+    println!();
+    println!("📊 Reindex Summary:");
+    println!("   ➕ Added: {}", added);
+    println!("   🔄 Updated: {}", updated);
+    println!("   ✅ Unchanged: {}", kept);
+    if prune {
+        println!("   🗑️  Removed: {}", pruned);
+    }
+    println!("📝 Workspace registry updated");
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,11 +1,14 @@
-use crate::config::WorkspaceRegistry;
-use crate::search::FuzzySearch;
+use crate::config::{GlobalConfig, WorkspaceRegistry};
+use crate::git::GitManager;
+use crate::search::{FuzzySearch, Query, git_status_of};
 use crate::error::Result;
 use std::io::{self, Write};
 
 pub async fn execute(pattern: &str) -> Result<()> {
-    let mut workspace_registry = WorkspaceRegistry::load_from_workspace()?;
-    let fuzzy_search = FuzzySearch::new();
+    let global_config = GlobalConfig::load()?;
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let git_manager = GitManager::new()?;
+    let fuzzy_search = FuzzySearch::new()?;
     
     let repositories = workspace_registry.list_repositories();
     
@@ -20,60 +23,85 @@ pub async fn execute(pattern: &str) -> Result<()> {
         .map(|(name, config)| (name.clone(), config.clone()))
         .collect();
     
-    // Perform fuzzy search
-    let results = fuzzy_search.search(&owned_repos, pattern);
+    // Perform search
+    let query = Query::parse(pattern)?;
+    let status_of = git_status_of(&git_manager, global_config.get_workspace_path());
+    let results = fuzzy_search.search_query(&owned_repos, &query, status_of);
     
     if results.is_empty() {
         println!("❌ No repositories found matching '{}'", pattern);
         return Ok(());
     }
     
-    // Select repository (interactive if multiple matches)
-    let selected = if results.len() == 1 {
-        Some(results.into_iter().next().unwrap())
-    } else {
-        fuzzy_search.interactive_select(results)?
-    };
+    // Select repositories (interactive if multiple matches)
+    let selected = fuzzy_search.interactive_select_many(results)?;
     
-    if let Some(selected_repo) = selected {
+    if selected.is_empty() {
+        println!("❌ No repository selected.");
+        return Ok(());
+    }
+    
+    let mut to_remove = Vec::new();
+    for selected_repo in selected {
         let full_path = workspace_registry.get_full_path(&selected_repo.repo_config)?;
-        
+        to_remove.push((selected_repo, full_path));
+    }
+    
+    if to_remove.len() == 1 {
         println!("📋 Repository to remove:");
+    } else {
+        println!("📋 {} repositories to remove:", to_remove.len());
+    }
+    for (selected_repo, full_path) in &to_remove {
         println!("   🔷 Name: {}", selected_repo.name);
         println!("   📁 Path: {}", full_path.display());
         println!("   🔗 URL: {}", selected_repo.repo_config.url);
         println!();
-        
-        // Confirm deletion
+    }
+    
+    // Confirm deletion
+    if to_remove.len() == 1 {
         print!("❓ Remove this repository from workspace? (y/N): ");
-        io::stdout().flush()?;
-        
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        
-        if input.trim().to_lowercase() != "y" {
-            println!("❌ Operation cancelled.");
-            return Ok(());
+    } else {
+        print!("❓ Remove these {} repositories from workspace? (y/N): ", to_remove.len());
+    }
+    io::stdout().flush()?;
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    
+    if input.trim().to_lowercase() != "y" {
+        println!("❌ Operation cancelled.");
+        return Ok(());
+    }
+    
+    // Ask about local files
+    print!("❓ Also delete local files? (y/N): ");
+    io::stdout().flush()?;
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let delete_files = input.trim().to_lowercase() == "y";
+    
+    // Remove from registry
+    WorkspaceRegistry::update(|registry| {
+        for (selected_repo, _) in &to_remove {
+            registry.remove_repository(&selected_repo.name);
         }
-        
-        // Ask about local files
-        print!("❓ Also delete local files? (y/N): ");
-        io::stdout().flush()?;
-        
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let delete_files = input.trim().to_lowercase() == "y";
-        
-        // Remove from registry
-        workspace_registry.remove_repository(&selected_repo.name);
-        workspace_registry.save()?;
-        
+        Ok(())
+    })?;
+    
+    if to_remove.len() == 1 {
         println!("✅ Repository removed from workspace registry.");
-        
-        // Delete local files if requested
+    } else {
+        println!("✅ {} repositories removed from workspace registry.", to_remove.len());
+    }
+    
+    // Delete local files if requested
+    for (_, full_path) in &to_remove {
         if delete_files {
             if full_path.exists() {
-                std::fs::remove_dir_all(&full_path)?;
+                std::fs::remove_dir_all(full_path)?;
                 println!("🗑️  Local files deleted: {}", full_path.display());
             } else {
                 println!("ℹ️  Local directory not found (already deleted?)");
@@ -81,8 +109,6 @@ pub async fn execute(pattern: &str) -> Result<()> {
         } else {
             println!("💾 Local files preserved: {}", full_path.display());
         }
-    } else {
-        println!("❌ No repository selected.");
     }
     
     Ok(())
@@ -0,0 +1,35 @@
+use clap::CommandFactory;
+use crate::cli::Cli;
+use crate::shell::{self, Shell};
+use crate::error::Result;
+
+pub async fn execute(shell: Shell) -> Result<()> {
+    // Point the wrapper at this very binary, so it works before p-bin is on PATH
+    let binary = std::env::current_exe()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "p-bin".to_string());
+    
+    print!("{}", shell::init_script(shell, &binary, &cd_commands(), &global_value_options()));
+    Ok(())
+}
+
+/// Subcommands (and their aliases) that accept `--output-cd`.
+fn cd_commands() -> Vec<String> {
+    Cli::command()
+        .get_subcommands()
+        .filter(|command| command.get_arguments().any(|arg| arg.get_id() == "output_cd"))
+        .flat_map(|command| std::iter::once(command.get_name()).chain(command.get_all_aliases()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Global options that take a value, such as `--workspace`, which the wrapper must skip
+/// together with that value to find the subcommand.
+fn global_value_options() -> Vec<String> {
+    Cli::command()
+        .get_arguments()
+        .filter(|arg| arg.is_global_set() && arg.get_action().takes_values())
+        .filter_map(|arg| arg.get_long())
+        .map(|long| format!("--{}", long))
+        .collect()
+}
\ No newline at end of file
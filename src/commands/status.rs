@@ -1,21 +1,33 @@
-use crate::config::{GlobalConfig, WorkspaceRegistry};
+use crate::config::{GlobalConfig, RepositoryConfig, WorkspaceRegistry};
 use crate::git::GitManager;
+use crate::cli::TagFilter;
 use crate::error::Result;
+use crate::output::{self, WorkspaceStatusRecord};
 
-pub async fn execute() -> Result<()> {
+pub async fn execute(filter: &TagFilter) -> Result<()> {
     let global_config = GlobalConfig::load()?;
     let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
     let git_manager = GitManager::new()?;
     
+    let repositories: Vec<_> = workspace_registry.list_repositories()
+        .into_iter()
+        .filter(|(_, config)| filter.matches(config))
+        .collect();
+    let summary = summarize(&global_config, &workspace_registry, &git_manager, &repositories)?;
+    
+    if output::is_machine() {
+        return output::emit_one(&summary);
+    }
+    
     println!("📊 Project Man Status");
     println!();
     
     // Workspace info
     println!("📁 Workspace:");
-    let workspace_path = global_config.get_workspace_path();
-    println!("   Path: {}", workspace_path.display());
+    println!("   Name: {}", summary.workspace);
+    println!("   Path: {}", summary.path.display());
     
-    if workspace_path.exists() {
+    if summary.exists {
         println!("   Status: ✅ Exists");
     } else {
         println!("   Status: ❌ Directory not found");
@@ -23,98 +35,151 @@ pub async fn execute() -> Result<()> {
         return Ok(());
     }
     
-    println!("   Created: {}", global_config.workspace.created_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    println!("   Created: {}", global_config.current_workspace().created_at.format("%Y-%m-%d %H:%M:%S UTC"));
     println!();
     
     // Repository statistics
-    let repositories = workspace_registry.list_repositories();
     println!("📦 Repositories:");
-    println!("   Total: {}", repositories.len());
+    println!("   Total: {}", summary.total);
+    if !filter.tags.is_empty() {
+        println!("   Tagged: {}", filter.tags.join(", "));
+    }
+    if !filter.exclude_tags.is_empty() {
+        println!("   Excluding: {}", filter.exclude_tags.join(", "));
+    }
     
-    if repositories.is_empty() {
+    if summary.total == 0 {
         println!("   💡 Use 'p add <repository>' to add repositories");
         return Ok(());
     }
     
-    let mut clean_count = 0;
-    let mut dirty_count = 0;
-    let mut ahead_count = 0;
-    let mut behind_count = 0;
-    let mut missing_count = 0;
-    let mut error_count = 0;
-    
-    for (_, repo_config) in &repositories {
-        let full_path = workspace_registry.get_full_path(repo_config)?;
-        
-        if !full_path.exists() {
-            missing_count += 1;
-            continue;
-        }
-        
-        match git_manager.get_repository_status(&full_path) {
-            Ok(status) => {
-                if status.is_clean {
-                    clean_count += 1;
-                } else {
-                    dirty_count += 1;
-                }
-                
-                if status.ahead > 0 {
-                    ahead_count += 1;
-                }
-                
-                if status.behind > 0 {
-                    behind_count += 1;
-                }
-            }
-            Err(_) => {
-                error_count += 1;
-            }
-        }
+    println!("   Clean: {}", summary.clean);
+    if summary.dirty > 0 {
+        println!("   Dirty: {}", summary.dirty);
     }
-    
-    println!("   Clean: {}", clean_count);
-    if dirty_count > 0 {
-        println!("   Dirty: {}", dirty_count);
+    if summary.ahead > 0 {
+        println!("   Ahead of remote: {}", summary.ahead);
+    }
+    if summary.behind > 0 {
+        println!("   Behind remote: {}", summary.behind);
     }
-    if ahead_count > 0 {
-        println!("   Ahead of remote: {}", ahead_count);
+    if summary.conflicted > 0 {
+        println!("   With merge conflicts: {}", summary.conflicted);
     }
-    if behind_count > 0 {
-        println!("   Behind remote: {}", behind_count);
+    if summary.stashed > 0 {
+        println!("   With stashed changes: {}", summary.stashed);
     }
-    if missing_count > 0 {
-        println!("   Missing directories: {}", missing_count);
+    if summary.detached > 0 {
+        println!("   Detached HEAD: {}", summary.detached);
     }
-    if error_count > 0 {
-        println!("   Errors: {}", error_count);
+    if summary.no_upstream > 0 {
+        println!("   Without upstream: {}", summary.no_upstream);
+    }
+    if summary.missing > 0 {
+        println!("   Missing directories: {}", summary.missing);
+    }
+    if summary.errors > 0 {
+        println!("   Errors: {}", summary.errors);
     }
     println!();
     
     // Configuration info
     println!("⚙️  Configuration:");
     println!("   Global config: ~/.config/project-man/config.toml");
-    println!("   Workspace registry: {}/project-man.yml", workspace_path.display());
+    println!("   Workspace registry: {}/project-man.yml", summary.path.display());
     println!("   Default git host: {}", global_config.git.default_host);
     println!("   Default protocol: {}", global_config.git.default_protocol);
     println!();
     
     // Recommendations
-    if dirty_count > 0 || ahead_count > 0 {
+    if summary.dirty > 0 || summary.ahead > 0 || summary.behind > 0 || summary.conflicted > 0 || summary.no_upstream > 0 || summary.missing > 0 {
         println!("💡 Recommendations:");
-        if dirty_count > 0 {
+        if summary.dirty > 0 {
             println!("   • Use 'p list' to see which repositories have uncommitted changes");
         }
-        if ahead_count > 0 {
+        if summary.ahead > 0 {
             println!("   • Consider pushing your local commits");
         }
-        if behind_count > 0 {
+        if summary.behind > 0 {
             println!("   • Use 'p sync' to pull latest changes");
         }
-        if missing_count > 0 {
-            println!("   • Use 'p remove <pattern>' to clean up missing repositories");
+        if summary.conflicted > 0 {
+            println!("   • Resolve the merge conflicts before syncing again");
+        }
+        if summary.no_upstream > 0 {
+            println!("   • Set an upstream (git branch -u) so those branches can be synced");
+        }
+        if summary.missing > 0 {
+            println!("   • Use 'p doctor --fix' to re-clone or forget missing repositories");
         }
     }
     
     Ok(())
+}
+
+/// Count how many of `repositories` are in each state.
+fn summarize(
+    global_config: &GlobalConfig,
+    workspace_registry: &WorkspaceRegistry,
+    git_manager: &GitManager,
+    repositories: &[(&String, &RepositoryConfig)],
+) -> Result<WorkspaceStatusRecord> {
+    let workspace_path = global_config.get_workspace_path();
+    let mut summary = WorkspaceStatusRecord {
+        workspace: global_config.current_workspace_name().to_string(),
+        path: workspace_path.to_path_buf(),
+        exists: workspace_path.exists(),
+        ..Default::default()
+    };
+    
+    if !summary.exists {
+        return Ok(summary);
+    }
+    summary.total = repositories.len();
+    
+    for (_, repo_config) in repositories {
+        let full_path = workspace_registry.get_full_path(repo_config)?;
+        
+        if !full_path.exists() {
+            summary.missing += 1;
+            continue;
+        }
+        
+        match git_manager.get_repository_status(&full_path) {
+            Ok(status) => {
+                if status.is_clean() {
+                    summary.clean += 1;
+                } else {
+                    summary.dirty += 1;
+                }
+                
+                if status.conflicted > 0 {
+                    summary.conflicted += 1;
+                }
+                
+                if status.stashes > 0 {
+                    summary.stashed += 1;
+                }
+                
+                if status.branch.is_none() {
+                    summary.detached += 1;
+                } else if status.upstream.is_none() {
+                    summary.no_upstream += 1;
+                }
+                
+                if status.ahead > 0 {
+                    summary.ahead += 1;
+                }
+                
+                if status.behind > 0 {
+                    summary.behind += 1;
+                }
+            }
+            Err(_) => {
+                summary.errors += 1;
+            }
+        }
+    }
+    
+    Ok(summary)
 }
\ No newline at end of file
@@ -0,0 +1,29 @@
+use crate::config::WorkspaceRegistry;
+use crate::git::SyncStrategy;
+use crate::search::select_repository;
+use crate::cli::StrategyCommands;
+use crate::error::{ProjectManError, Result};
+
+pub async fn execute(subcommand: StrategyCommands) -> Result<()> {
+    match subcommand {
+        StrategyCommands::Set { pattern, strategy } => set_strategy(&pattern, Some(strategy)).await,
+        StrategyCommands::Unset { pattern } => set_strategy(&pattern, None).await,
+    }
+}
+
+async fn set_strategy(pattern: &str, strategy: Option<SyncStrategy>) -> Result<()> {
+    let Some(name) = select_repository(pattern)?.map(|result| result.name) else {
+        return Ok(());
+    };
+    
+    WorkspaceRegistry::update(|registry| {
+        let repo = registry.repositories.get_mut(&name)
+            .ok_or_else(|| ProjectManError::RepositoryNotFound(name.clone()))?;
+        repo.sync_strategy = strategy;
+        Ok(())
+    })?;
+    
+    println!("✅ {} will be synced with '{}'", name, strategy.unwrap_or_default());
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,100 +1,328 @@
-use crate::config::WorkspaceRegistry;
-use crate::git::{GitManager, SyncResult};
-use crate::search::FuzzySearch;
-use crate::error::Result;
+use std::collections::BTreeSet;
+use std::io::{self, IsTerminal, Write};
+use std::sync::Arc;
+use crossterm::{cursor, queue, terminal::{self, ClearType}};
+use tokio::sync::{mpsc, Semaphore};
+use crate::config::{GlobalConfig, WorkspaceRegistry};
+use crate::git::{GitManager, SkipReason, SyncResult, SyncStrategy};
+use crate::search::{FuzzySearch, Query, git_status_of};
+use crate::cli::TagFilter;
+use crate::error::{ProjectManError, Result};
+use crate::output::{self, SyncRecord};
 
-pub async fn execute(pattern: Option<&str>) -> Result<()> {
-    let mut workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+pub const DEFAULT_JOBS: usize = 8;
+// New commits listed under each updated repository
+const CHANGELOG_LINES: usize = 5;
+
+enum SyncEvent {
+    Started(String),
+    Finished(String, SyncStrategy, Result<SyncResult>),
+}
+
+pub async fn execute(pattern: Option<&str>, filter: &TagFilter, interactive: bool, jobs: usize, strategy: Option<SyncStrategy>) -> Result<()> {
+    let global_config = GlobalConfig::load()?;
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
     let git_manager = GitManager::new()?;
     
-    let repositories = workspace_registry.list_repositories();
+    let repositories: Vec<_> = workspace_registry.list_repositories()
+        .into_iter()
+        .filter(|(_, config)| filter.matches(config))
+        .collect();
     
     if repositories.is_empty() {
+        if output::is_machine() {
+            return output::emit::<SyncRecord>(&[]);
+        }
         println!("📋 No repositories found in workspace.");
         return Ok(());
     }
     
-    let repos_to_sync: Vec<(String, _)> = if let Some(pattern) = pattern {
-        // Filter repositories by pattern
-        let fuzzy_search = FuzzySearch::new();
-        let owned_repos: Vec<(String, _)> = repositories
-            .into_iter()
-            .map(|(name, config)| (name.clone(), config.clone()))
-            .collect();
-        
-        let results = fuzzy_search.search(&owned_repos, pattern);
-        
-        if results.is_empty() {
+    let fuzzy_search = FuzzySearch::new()?;
+    let owned_repos: Vec<(String, _)> = repositories
+        .into_iter()
+        .map(|(name, config)| (name.clone(), config.clone()))
+        .collect();
+    
+    // Without a pattern every repository is synced
+    let query = Query::parse(pattern.unwrap_or_default())?;
+    let status_of = git_status_of(&git_manager, global_config.get_workspace_path());
+    let mut results = fuzzy_search.search_query(&owned_repos, &query, status_of);
+    
+    if results.is_empty() {
+        if output::is_machine() {
+            return output::emit::<SyncRecord>(&[]);
+        }
+        if let Some(pattern) = pattern {
             println!("❌ No repositories found matching '{}'", pattern);
+        }
+        return Ok(());
+    }
+    
+    if interactive {
+        results = fuzzy_search.interactive_select_many(results)?;
+        if results.is_empty() {
+            if output::is_machine() {
+                return output::emit::<SyncRecord>(&[]);
+            }
+            println!("❌ No repository selected.");
             return Ok(());
         }
-        
-        results.into_iter()
-            .map(|r| (r.name, r.repo_config))
-            .collect()
-    } else {
-        // Sync all repositories
-        repositories.into_iter()
-            .map(|(name, config)| (name.clone(), config.clone()))
-            .collect()
-    };
-    
-    println!("🔄 Synchronizing {} repositories...", repos_to_sync.len());
-    println!();
+    }
+    
+    let repos_to_sync: Vec<(String, _)> = results.into_iter()
+        .map(|r| (r.name, r.repo_config))
+        .collect();
+    let total = repos_to_sync.len();
+    let jobs = jobs.max(1);
+    
+    if !output::is_machine() {
+        println!("🔄 Synchronizing {} repositories ({} at a time)...", total, jobs.min(total));
+        println!();
+    }
     
+    let mut records = Vec::new();
     let mut success_count = 0;
     let mut error_count = 0;
+    let mut skipped_count = 0;
+    let mut synced = Vec::new();
+    
+    // Each pull runs on the blocking pool; the semaphore bounds how many git processes run at once
+    let git_manager = Arc::new(git_manager);
+    let permits = Arc::new(Semaphore::new(jobs));
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut progress = SyncProgress::new(total);
     
     for (name, repo_config) in repos_to_sync {
         let full_path = workspace_registry.get_full_path(&repo_config)?;
-        
-        print!("🔄 Syncing {}: ", name);
-        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+        let strategy = strategy.or(repo_config.sync_strategy).unwrap_or_default();
         
         if !full_path.exists() {
-            println!("❌ Directory not found");
             error_count += 1;
+            records.push(SyncRecord::missing(&name, strategy));
+            progress.finish(&name, &[format!("❌ {}: Directory not found", name)])?;
             continue;
         }
         
-        match git_manager.sync_repository(&full_path) {
-            Ok(SyncResult::UpToDate) => {
-                println!("✅ Up to date");
-                success_count += 1;
+        let git_manager = Arc::clone(&git_manager);
+        let permits = Arc::clone(&permits);
+        let sender = sender.clone();
+        
+        tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let _ = sender.send(SyncEvent::Started(name.clone()));
+            
+            let result = tokio::task::spawn_blocking(move || git_manager.sync_repository(&full_path, strategy))
+                .await
+                .unwrap_or_else(|e| Err(ProjectManError::Git(format!("Sync task failed: {}", e))));
+            let _ = sender.send(SyncEvent::Finished(name, strategy, result));
+        });
+    }
+    drop(sender);
+    
+    while let Some(event) = receiver.recv().await {
+        match event {
+            SyncEvent::Started(name) => progress.start(name)?,
+            SyncEvent::Finished(name, strategy, result) => {
+                records.push(SyncRecord::new(&name, strategy, &result));
+                let lines = match result {
+                    Ok(SyncResult::UpToDate) => {
+                        success_count += 1;
+                        vec![format!("✅ {}: Up to date", name)]
+                    }
+                    Ok(SyncResult::Fetched { behind, new_commits }) => {
+                        success_count += 1;
+                        let mut lines = vec![format!("📥 {}: Fetched ({} new commits not merged)", name, behind)];
+                        lines.extend(changelog(behind, &new_commits));
+                        lines
+                    }
+                    Ok(SyncResult::Updated { commits_pulled, new_commits }) => {
+                        success_count += 1;
+                        synced.push(name.clone());
+                        let mut lines = vec![format!("✅ {}: Updated ({} commits)", name, commits_pulled)];
+                        lines.extend(changelog(commits_pulled, &new_commits));
+                        lines
+                    }
+                    Ok(SyncResult::Rebased { commits_pulled, local_commits, new_commits }) => {
+                        success_count += 1;
+                        synced.push(name.clone());
+                        let mut lines = vec![format!("✅ {}: Rebased {} local commits onto {} new commits", name, local_commits, commits_pulled)];
+                        lines.extend(changelog(commits_pulled, &new_commits));
+                        lines
+                    }
+                    Ok(SyncResult::Conflict { ahead, behind }) => {
+                        error_count += 1;
+                        vec![
+                            format!("⚠️  {}: Diverged (ahead: {}, behind: {})", name, ahead, behind),
+                            "   💡 Merge manually or sync with --strategy rebase".to_string(),
+                        ]
+                    }
+                    Ok(SyncResult::RebaseConflict { ahead, behind }) => {
+                        error_count += 1;
+                        vec![
+                            format!("⚠️  {}: Rebase conflicts (ahead: {}, behind: {}), left as it was", name, ahead, behind),
+                            "   💡 Manual rebase or merge required".to_string(),
+                        ]
+                    }
+                    Ok(SyncResult::AutostashConflict { commits_pulled, new_commits }) => {
+                        error_count += 1;
+                        synced.push(name.clone());
+                        let mut lines = vec![format!("⚠️  {}: Rebased onto {} new commits, but local changes conflict", name, commits_pulled)];
+                        lines.extend(changelog(commits_pulled, &new_commits));
+                        lines.push("   💡 Your changes are kept in 'git stash list'".to_string());
+                        lines
+                    }
+                    Ok(SyncResult::Skipped(reason)) => {
+                        skipped_count += 1;
+                        let reason = match reason {
+                            SkipReason::DetachedHead => "HEAD is detached".to_string(),
+                            SkipReason::NoUpstream { branch } => format!("branch '{}' has no upstream", branch),
+                            SkipReason::LocalChanges => "local changes are in the way (try --strategy rebase-autostash)".to_string(),
+                        };
+                        vec![format!("⏭️  {}: Skipped, {}", name, reason)]
+                    }
+                    Err(e) => {
+                        error_count += 1;
+                        vec![format!("❌ {}: Failed: {}", name, e)]
+                    }
+                };
+                progress.finish(&name, &lines)?;
             }
-            Ok(SyncResult::Updated { commits_pulled }) => {
-                println!("✅ Updated ({} commits)", commits_pulled);
-                // Update last sync time
-                if let Err(e) = workspace_registry.update_last_sync(&name) {
+        }
+    }
+    
+    // Record sync times once, merging with changes other processes made meanwhile
+    if !synced.is_empty() {
+        WorkspaceRegistry::update(|registry| {
+            for name in &synced {
+                if let Err(e) = registry.update_last_sync(name) {
                     eprintln!("⚠️  Failed to update sync time: {}", e);
                 }
-                success_count += 1;
-            }
-            Ok(SyncResult::Conflict { ahead, behind }) => {
-                println!("⚠️  Conflict (ahead: {}, behind: {})", ahead, behind);
-                println!("   💡 Manual merge required");
-                error_count += 1;
             }
-            Err(e) => {
-                println!("❌ Failed: {}", e);
-                error_count += 1;
-            }
-        }
+            Ok(())
+        })?;
     }
     
-    // Save registry if any syncs were successful
-    if success_count > 0 {
-        workspace_registry.save()?;
+    if output::is_machine() {
+        records.sort_by(|a, b| a.name.cmp(&b.name));
+        return output::emit(&records);
     }
     
     println!();
     println!("📊 Sync Summary:");
     println!("   ✅ Successful: {}", success_count);
     println!("   ❌ Failed: {}", error_count);
+    if skipped_count > 0 {
+        println!("   ⏭️  Skipped: {}", skipped_count);
+    }
     
     if error_count > 0 {
         println!("💡 Use 'p list' to check repository status");
     }
     
     Ok(())
+}
+
+/// Indented one-line summaries of the newest commits, noting how many more there are.
+fn changelog(count: usize, new_commits: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = new_commits.iter()
+        .take(CHANGELOG_LINES)
+        .map(|commit| format!("   • {}", commit))
+        .collect();
+    
+    let shown = lines.len();
+    if count > shown {
+        lines.push(format!("   … and {} more", count - shown));
+    }
+    
+    lines
+}
+
+/// Prints each finished repository as a permanent line and, on a terminal, keeps a live
+/// area below them listing the repositories still being synced. Prints nothing when
+/// results are written as records instead.
+struct SyncProgress {
+    silent: bool,
+    live: bool,
+    total: usize,
+    done: usize,
+    // Repositories currently syncing, keyed by name so the area has a stable order
+    running: BTreeSet<String>,
+    drawn_rows: u16,
+}
+
+impl SyncProgress {
+    fn new(total: usize) -> Self {
+        Self {
+            silent: output::is_machine(),
+            live: io::stdout().is_terminal(),
+            total,
+            done: 0,
+            running: BTreeSet::new(),
+            drawn_rows: 0,
+        }
+    }
+    
+    fn start(&mut self, name: String) -> Result<()> {
+        self.running.insert(name);
+        self.redraw(&[])
+    }
+    
+    fn finish(&mut self, name: &str, lines: &[String]) -> Result<()> {
+        self.running.remove(name);
+        self.done += 1;
+        self.redraw(lines)
+    }
+    
+    fn redraw(&mut self, finished: &[String]) -> Result<()> {
+        if self.silent {
+            return Ok(());
+        }
+        
+        let mut out = io::stdout().lock();
+        
+        if !self.live {
+            for line in finished {
+                writeln!(out, "{}", line)?;
+            }
+            return Ok(());
+        }
+        
+        if self.drawn_rows > 0 {
+            queue!(out, cursor::MoveUp(self.drawn_rows), cursor::MoveToColumn(0))?;
+        }
+        queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+        
+        for line in finished {
+            writeln!(out, "{}", line)?;
+        }
+        
+        // Some terminals report no size at all; assume the classic dimensions then
+        let (width, height) = terminal::size()
+            .ok()
+            .filter(|&(width, height)| width > 0 && height > 0)
+            .unwrap_or((80, 24));
+        let width = width as usize;
+        // Leave room for the counter line and never scroll the live area off screen
+        let max_rows = (height as usize).saturating_sub(2).max(1);
+        
+        let mut rows = 0;
+        if self.done < self.total {
+            for name in self.running.iter().take(max_rows) {
+                writeln!(out, "{}", truncate(&format!("   🔄 {}", name), width))?;
+                rows += 1;
+            }
+            writeln!(out, "{}", truncate(&format!("   ⏳ {}/{} done, {} running", self.done, self.total, self.running.len()), width))?;
+            rows += 1;
+        }
+        self.drawn_rows = rows;
+        
+        out.flush()?;
+        Ok(())
+    }
+}
+
+// Keeps live lines on a single row so moving up over them stays accurate
+fn truncate(line: &str, width: usize) -> String {
+    // Emoji take two columns; leave a little slack rather than measuring exactly
+    line.chars().take(width.saturating_sub(2)).collect()
 }
\ No newline at end of file
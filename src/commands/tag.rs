@@ -0,0 +1,94 @@
+use crate::config::{WorkspaceRegistry, validate_tag};
+use crate::search::select_repository;
+use crate::cli::TagCommands;
+use crate::error::{ProjectManError, Result};
+use std::collections::BTreeMap;
+
+pub async fn execute(subcommand: TagCommands) -> Result<()> {
+    match subcommand {
+        TagCommands::Add { pattern, tags } => add_tags(&pattern, &tags).await,
+        TagCommands::Remove { pattern, tags } => remove_tags(&pattern, &tags).await,
+        TagCommands::List => list_tags().await,
+    }
+}
+
+async fn add_tags(pattern: &str, tags: &[String]) -> Result<()> {
+    for tag in tags {
+        validate_tag(tag)?;
+    }
+    
+    let Some(name) = select_repository(pattern)?.map(|result| result.name) else {
+        return Ok(());
+    };
+    
+    let current = WorkspaceRegistry::update(|registry| {
+        let repo = registry.repositories.get_mut(&name)
+            .ok_or_else(|| ProjectManError::RepositoryNotFound(name.clone()))?;
+        for tag in tags {
+            repo.add_tag(tag);
+        }
+        Ok(repo.tags.clone())
+    })?;
+    
+    println!("✅ Tagged {}", name);
+    println!("🏷️  Tags: {}", current.join(", "));
+    
+    Ok(())
+}
+
+async fn remove_tags(pattern: &str, tags: &[String]) -> Result<()> {
+    let Some(name) = select_repository(pattern)?.map(|result| result.name) else {
+        return Ok(());
+    };
+    
+    let current = WorkspaceRegistry::update(|registry| {
+        let repo = registry.repositories.get_mut(&name)
+            .ok_or_else(|| ProjectManError::RepositoryNotFound(name.clone()))?;
+        for tag in tags {
+            if !repo.remove_tag(tag) {
+                println!("ℹ️  {} was not tagged '{}'", name, tag);
+            }
+        }
+        Ok(repo.tags.clone())
+    })?;
+    
+    println!("✅ Updated tags of {}", name);
+    if current.is_empty() {
+        println!("🏷️  Tags: (none)");
+    } else {
+        println!("🏷️  Tags: {}", current.join(", "));
+    }
+    
+    Ok(())
+}
+
+async fn list_tags() -> Result<()> {
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    
+    let mut tagged: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (name, repo_config) in workspace_registry.list_repositories() {
+        for tag in &repo_config.tags {
+            tagged.entry(tag.as_str()).or_default().push(name.as_str());
+        }
+    }
+    
+    if tagged.is_empty() {
+        println!("🏷️  No tags in workspace.");
+        println!("💡 Use 'p tag add <pattern> <tag>' to tag repositories.");
+        return Ok(());
+    }
+    
+    println!("🏷️  Tags in workspace:");
+    println!();
+    
+    for (tag, mut names) in tagged {
+        names.sort();
+        println!("🔷 {} ({} repositories)", tag, names.len());
+        for name in names {
+            println!("   {}", name);
+        }
+        println!();
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::config::{GlobalConfig, WorkspaceRegistry};
+use crate::cli::WorkspaceCommands;
+use crate::error::Result;
+use std::path::Path;
+
+pub async fn execute(subcommand: WorkspaceCommands) -> Result<()> {
+    match subcommand {
+        WorkspaceCommands::Add { name, path } => add_workspace(&name, &path).await,
+        WorkspaceCommands::Switch { name } => switch_workspace(&name).await,
+        WorkspaceCommands::List => list_workspaces().await,
+        WorkspaceCommands::Remove { name } => remove_workspace(&name).await,
+    }
+}
+
+async fn add_workspace(name: &str, path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path)?;
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    
+    GlobalConfig::update(|config| config.add_workspace(name, path.clone()))?;
+    
+    // Give the new workspace an empty registry so it is usable right away
+    let registry_path = path.join("project-man.yml");
+    if !registry_path.exists() {
+        WorkspaceRegistry::new().save_to(&registry_path)?;
+    }
+    
+    println!("✅ Workspace '{}' added", name);
+    println!("📁 Path: {}", path.display());
+    println!("💡 Use 'p workspace switch {}' to make it active", name);
+    
+    Ok(())
+}
+
+async fn switch_workspace(name: &str) -> Result<()> {
+    let path = GlobalConfig::update(|config| {
+        config.switch_workspace(name)?;
+        Ok(config.get_workspace_path().clone())
+    })?;
+    
+    println!("✅ Switched to workspace '{}'", name);
+    println!("📁 Path: {}", path.display());
+    
+    Ok(())
+}
+
+async fn list_workspaces() -> Result<()> {
+    let config = GlobalConfig::load()?;
+    
+    println!("🗂️  Workspaces:");
+    println!();
+    
+    for (name, workspace) in &config.workspaces {
+        let marker = if *name == config.active_workspace { "➤" } else { " " };
+        let exists = if workspace.path.exists() { "" } else { " (missing)" };
+        
+        println!("{} {}", marker, name);
+        println!("   📁 {}{}", workspace.path.display(), exists);
+    }
+    
+    println!();
+    println!("📊 Total workspaces: {}", config.workspaces.len());
+    
+    Ok(())
+}
+
+async fn remove_workspace(name: &str) -> Result<()> {
+    let removed = GlobalConfig::update(|config| config.remove_workspace(name))?;
+    
+    println!("✅ Workspace '{}' removed from configuration", name);
+    println!("💾 Files preserved: {}", removed.path.display());
+    
+    Ok(())
+}
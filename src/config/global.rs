@@ -1,17 +1,53 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use crate::config::{WorkspaceConfig, GitConfig, SearchConfig, UiConfig, ensure_config_dir};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use crate::config::lock::{FileLock, write_atomic};
+use crate::config::{WorkspaceConfig, GitConfig, SearchConfig, UiConfig, ensure_config_dir, workspace_override};
 use crate::error::{ProjectManError, Result};
 
+pub const DEFAULT_WORKSPACE_NAME: &str = "default";
+
+/// Keys understood by `GlobalConfig::get_value`, in display order.
+pub const CONFIG_KEYS: &[&str] = &[
+    "workspace.active",
+    "workspace.name",
+    "workspace.path",
+    "workspace.created_at",
+    "git.default_host",
+    "git.default_protocol",
+    "git.backend",
+    "search.fuzzy_threshold",
+    "search.max_results",
+    "search.case_sensitive",
+    "ui.confirm_destructive_actions",
+    "ui.use_colors",
+    "ui.pager",
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GlobalConfig {
-    pub workspace: WorkspaceConfig,
+    #[serde(default)]
+    pub active_workspace: String,
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, WorkspaceConfig>,
     pub git: GitConfig,
     pub search: SearchConfig,
     pub ui: UiConfig,
+    
+    // Single-workspace layout written by older versions; folded into `workspaces` on load
+    #[serde(default, skip_serializing)]
+    workspace: Option<WorkspaceConfig>,
+    
+    // Workspace selected for this invocation via `--workspace`
+    #[serde(skip)]
+    selected_workspace: Option<String>,
 }
 
 impl GlobalConfig {
+    pub fn exists() -> Result<bool> {
+        Ok(ensure_config_dir()?.join("config.toml").exists())
+    }
+    
     pub fn load() -> Result<Self> {
         let config_dir = ensure_config_dir()?;
         let config_path = config_dir.join("config.toml");
@@ -23,35 +59,121 @@ impl GlobalConfig {
         }
         
         let content = std::fs::read_to_string(&config_path)?;
-        let config: GlobalConfig = toml::from_str(&content)?;
+        let mut config: GlobalConfig = toml::from_str(&content)?;
+        
+        if let Some(legacy) = config.workspace.take() {
+            config.workspaces.entry(DEFAULT_WORKSPACE_NAME.to_string()).or_insert(legacy);
+            if config.active_workspace.is_empty() {
+                config.active_workspace = DEFAULT_WORKSPACE_NAME.to_string();
+            }
+        }
+        
+        if !config.workspaces.contains_key(&config.active_workspace) {
+            return Err(ProjectManError::Config(format!(
+                "Active workspace '{}' is not configured. Run 'p workspace switch <name>' to select one.",
+                config.active_workspace
+            )));
+        }
+        
+        if let Some(name) = workspace_override() {
+            if !config.workspaces.contains_key(name) {
+                return Err(ProjectManError::UnknownWorkspace(name.to_string()));
+            }
+            config.selected_workspace = Some(name.to_string());
+        }
+        
         Ok(config)
     }
     
-    pub fn save(&self) -> Result<()> {
-        let config_dir = ensure_config_dir()?;
-        let config_path = config_dir.join("config.toml");
+    /// Apply `f` to the on-disk configuration while holding its lock, then save.
+    pub fn update<T>(f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let config_path = ensure_config_dir()?.join("config.toml");
+        let _lock = FileLock::acquire(&config_path)?;
         
+        let mut config = Self::load()?;
+        let result = f(&mut config)?;
+        config.write_to(&config_path)?;
+        
+        Ok(result)
+    }
+    
+    pub fn save(&self) -> Result<()> {
+        let config_path = ensure_config_dir()?.join("config.toml");
+        let _lock = FileLock::acquire(&config_path)?;
+        self.write_to(&config_path)
+    }
+    
+    fn write_to(&self, config_path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self)
             .map_err(|e| ProjectManError::Config(format!("Failed to serialize config: {}", e)))?;
         
-        std::fs::write(&config_path, content)?;
-        Ok(())
+        write_atomic(config_path, &content)
     }
     
-    pub fn new(workspace_path: PathBuf) -> Self {
+    pub fn new(name: &str, workspace_path: PathBuf) -> Self {
+        let mut workspaces = BTreeMap::new();
+        workspaces.insert(name.to_string(), WorkspaceConfig {
+            path: workspace_path,
+            created_at: chrono::Utc::now(),
+        });
+        
         Self {
-            workspace: WorkspaceConfig {
-                path: workspace_path,
-                created_at: chrono::Utc::now(),
-            },
+            active_workspace: name.to_string(),
+            workspaces,
             git: GitConfig::default(),
             search: SearchConfig::default(),
             ui: UiConfig::default(),
+            workspace: None,
+            selected_workspace: None,
         }
     }
     
+    /// Name of the workspace commands operate on: the `--workspace` override, else the active one.
+    pub fn current_workspace_name(&self) -> &str {
+        self.selected_workspace.as_deref().unwrap_or(&self.active_workspace)
+    }
+    
+    pub fn current_workspace(&self) -> &WorkspaceConfig {
+        // `load` and `new` guarantee the current workspace is registered
+        &self.workspaces[self.current_workspace_name()]
+    }
+    
     pub fn get_workspace_path(&self) -> &PathBuf {
-        &self.workspace.path
+        &self.current_workspace().path
+    }
+    
+    pub fn add_workspace(&mut self, name: &str, path: PathBuf) -> Result<()> {
+        if self.workspaces.contains_key(name) {
+            return Err(ProjectManError::Config(format!("Workspace '{}' already exists", name)));
+        }
+        
+        self.workspaces.insert(name.to_string(), WorkspaceConfig {
+            path,
+            created_at: chrono::Utc::now(),
+        });
+        Ok(())
+    }
+    
+    pub fn switch_workspace(&mut self, name: &str) -> Result<()> {
+        if !self.workspaces.contains_key(name) {
+            return Err(ProjectManError::UnknownWorkspace(name.to_string()));
+        }
+        
+        self.active_workspace = name.to_string();
+        self.selected_workspace = None;
+        Ok(())
+    }
+    
+    pub fn remove_workspace(&mut self, name: &str) -> Result<WorkspaceConfig> {
+        if name == self.active_workspace {
+            return Err(ProjectManError::Config(format!(
+                "Cannot remove the active workspace '{}'. Switch to another workspace first.",
+                name
+            )));
+        }
+        
+        self.workspaces.remove(name)
+            .ok_or_else(|| ProjectManError::UnknownWorkspace(name.to_string()))
     }
     
     pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
@@ -66,13 +188,34 @@ impl GlobalConfig {
                     ));
                 }
             }
+            "git.backend" => {
+                if value == "library" || value == "subprocess" {
+                    self.git.backend = value.to_string();
+                } else {
+                    return Err(ProjectManError::Config(
+                        "git.backend must be 'library' or 'subprocess'".to_string()
+                    ));
+                }
+            }
             "search.fuzzy_threshold" => {
-                self.search.fuzzy_threshold = value.parse()
+                let threshold: f64 = value.parse()
                     .map_err(|_| ProjectManError::Config("Invalid fuzzy_threshold value".to_string()))?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(ProjectManError::Config(
+                        "search.fuzzy_threshold must be between 0.0 and 1.0".to_string()
+                    ));
+                }
+                self.search.fuzzy_threshold = threshold;
             }
             "search.max_results" => {
-                self.search.max_results = value.parse()
+                let max_results: usize = value.parse()
                     .map_err(|_| ProjectManError::Config("Invalid max_results value".to_string()))?;
+                if max_results == 0 {
+                    return Err(ProjectManError::Config(
+                        "search.max_results must be at least 1".to_string()
+                    ));
+                }
+                self.search.max_results = max_results;
             }
             "search.case_sensitive" => {
                 self.search.case_sensitive = value.parse()
@@ -94,10 +237,13 @@ impl GlobalConfig {
     
     pub fn get_value(&self, key: &str) -> Result<String> {
         let value = match key {
-            "workspace.path" => self.workspace.path.to_string_lossy().to_string(),
-            "workspace.created_at" => self.workspace.created_at.to_rfc3339(),
+            "workspace.active" => self.active_workspace.clone(),
+            "workspace.name" => self.current_workspace_name().to_string(),
+            "workspace.path" => self.get_workspace_path().to_string_lossy().to_string(),
+            "workspace.created_at" => self.current_workspace().created_at.to_rfc3339(),
             "git.default_host" => self.git.default_host.clone(),
             "git.default_protocol" => self.git.default_protocol.clone(),
+            "git.backend" => self.git.backend.clone(),
             "search.fuzzy_threshold" => self.search.fuzzy_threshold.to_string(),
             "search.max_results" => self.search.max_results.to_string(),
             "search.case_sensitive" => self.search.case_sensitive.to_string(),
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use crate::config::GlobalConfig;
+use crate::config::lock::{FileLock, write_atomic};
+use crate::error::{ProjectManError, Result};
+
+/// Per-workspace record of which repositories `p go` and `p add` jumped to.
+///
+/// Kept out of `project-man.yml` so that navigating doesn't rewrite the registry.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VisitHistory {
+    pub visits: HashMap<String, VisitStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VisitStats {
+    pub count: u32,
+    pub last_visit: DateTime<Utc>,
+}
+
+impl VisitHistory {
+    pub fn load() -> Result<Self> {
+        let history_path = Self::history_path()?;
+        
+        if !history_path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = std::fs::read_to_string(&history_path)?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+    
+    pub fn record_visit(name: &str) -> Result<()> {
+        let history_path = Self::history_path()?;
+        let _lock = FileLock::acquire(&history_path)?;
+        
+        let mut history = Self::load()?;
+        let now = Utc::now();
+        history.visits
+            .entry(name.to_string())
+            .and_modify(|stats| {
+                stats.count = stats.count.saturating_add(1);
+                stats.last_visit = now;
+            })
+            .or_insert(VisitStats { count: 1, last_visit: now });
+        
+        write_atomic(&history_path, &serde_yaml::to_string(&history)?)
+    }
+    
+    /// Visit count weighted by how recently the repository was last visited.
+    pub fn frecency(&self, name: &str) -> f64 {
+        let Some(stats) = self.visits.get(name) else {
+            return 0.0;
+        };
+        
+        let age = Utc::now().signed_duration_since(stats.last_visit);
+        let recency = if age.num_hours() < 1 {
+            4.0
+        } else if age.num_days() < 1 {
+            2.0
+        } else if age.num_weeks() < 1 {
+            0.5
+        } else {
+            0.25
+        };
+        
+        stats.count as f64 * recency
+    }
+    
+    fn history_path() -> Result<PathBuf> {
+        let global_config = GlobalConfig::load()?;
+        let workspace_path = global_config.get_workspace_path();
+        
+        if !workspace_path.exists() {
+            return Err(ProjectManError::WorkspaceNotFound);
+        }
+        
+        Ok(workspace_path.join("project-man.history.yml"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    
+    fn history(visits: &[(&str, u32, Duration)]) -> VisitHistory {
+        let visits = visits
+            .iter()
+            .map(|&(name, count, age)| (name.to_string(), VisitStats { count, last_visit: Utc::now() - age }))
+            .collect();
+        VisitHistory { visits }
+    }
+    
+    #[test]
+    fn frecency_weights_visits_by_recency() {
+        let history = history(&[
+            ("hour", 1, Duration::minutes(5)),
+            ("day", 1, Duration::hours(5)),
+            ("week", 1, Duration::days(3)),
+            ("older", 1, Duration::weeks(3)),
+        ]);
+        
+        assert_eq!(history.frecency("hour"), 4.0);
+        assert_eq!(history.frecency("day"), 2.0);
+        assert_eq!(history.frecency("week"), 0.5);
+        assert_eq!(history.frecency("older"), 0.25);
+        assert_eq!(history.frecency("never"), 0.0);
+    }
+    
+    #[test]
+    fn frequent_old_visits_can_outweigh_a_recent_one() {
+        let history = history(&[("daily", 20, Duration::days(2)), ("once", 1, Duration::minutes(1))]);
+        
+        assert!(history.frecency("daily") > history.frecency("once"));
+    }
+}
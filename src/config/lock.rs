@@ -0,0 +1,130 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::error::Result;
+
+/// Advisory exclusive lock on a sidecar `<file>.lock`, released on drop.
+///
+/// Every writer of a shared file takes this lock around its load-modify-save
+/// cycle, so concurrent `p` invocations queue up instead of clobbering each other.
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    pub fn acquire(target: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(false)
+            .open(sidecar_path(target, "lock"))?;
+        
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                eprintln!("⏳ Waiting for another p process to release {}", target.display());
+                file.lock()?;
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        
+        Ok(Self { file })
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// Replace `path` with `content` so readers only ever see the old or the new file.
+///
+/// An existing file keeps its permissions, so a registry made private stays private.
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let tmp_path = sidecar_path(path, &format!("tmp.{}", std::process::id()));
+    
+    let result = (|| -> Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)?;
+        sync_parent_dir(path)
+    })();
+    
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    
+    result
+}
+
+/// Make the rename itself durable; without this a crash can bring back the old file.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()?;
+    Ok(())
+}
+
+// Directories can't be opened as files on Windows, where the rename is durable enough
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("p-lock-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    #[test]
+    fn replaces_content_without_leaving_temp_files() {
+        let dir = scratch_dir("replace");
+        let path = dir.join("project-man.yml");
+        
+        write_atomic(&path, "old").unwrap();
+        write_atomic(&path, "new").unwrap();
+        
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn keeps_permissions_of_the_replaced_file() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let dir = scratch_dir("permissions");
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        
+        write_atomic(&path, "new").unwrap();
+        
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,15 +1,22 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use chrono::{DateTime, Utc};
 use crate::error::{ProjectManError, Result};
 
 pub mod global;
+pub mod history;
+pub mod lock;
+pub mod schema;
 pub mod workspace;
 
-pub use global::GlobalConfig;
-pub use workspace::{WorkspaceRegistry, RepositoryConfig};
+pub use global::{GlobalConfig, CONFIG_KEYS};
+pub use history::VisitHistory;
+pub use workspace::{WorkspaceRegistry, RepositoryConfig, validate_tag, validate_alias};
 
-#[derive(Debug, Serialize, Deserialize)]
+static WORKSPACE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspaceConfig {
     pub path: PathBuf,
     pub created_at: DateTime<Utc>,
@@ -20,6 +27,13 @@ pub struct GitConfig {
     pub default_host: String,
     pub default_protocol: String,
     pub ssh_key_path: Option<PathBuf>,
+    /// `library` (libgit2, falling back to the git CLI) or `subprocess` (git CLI only)
+    #[serde(default = "default_git_backend")]
+    pub backend: String,
+}
+
+fn default_git_backend() -> String {
+    "library".to_string()
 }
 
 impl Default for GitConfig {
@@ -28,6 +42,7 @@ impl Default for GitConfig {
             default_host: "github.com".to_string(),
             default_protocol: "ssh".to_string(),
             ssh_key_path: None,
+            backend: default_git_backend(),
         }
     }
 }
@@ -76,4 +91,13 @@ pub fn ensure_config_dir() -> Result<PathBuf> {
     let config_dir = get_config_dir()?;
     std::fs::create_dir_all(&config_dir)?;
     Ok(config_dir)
-}
\ No newline at end of file
+}
+
+/// Select a workspace for this invocation instead of the active one (`--workspace`).
+pub fn set_workspace_override(name: &str) {
+    let _ = WORKSPACE_OVERRIDE.set(name.to_string());
+}
+
+pub fn workspace_override() -> Option<&'static str> {
+    WORKSPACE_OVERRIDE.get().map(|name| name.as_str())
+}
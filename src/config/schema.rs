@@ -0,0 +1,127 @@
+use serde_yaml::{Mapping, Value};
+use crate::error::{ProjectManError, Result};
+
+/// Schema version written by this binary.
+///
+/// Bump it whenever the on-disk shape of `project-man.yml` changes, and append the
+/// matching step to `MIGRATIONS`; older binaries then refuse the file instead of
+/// misreading it. Since v3 the registry and each repository entry collect keys they
+/// don't know in their `extra` field and write them back on save, so a new optional
+/// field no longer needs a bump. Binaries before v3 dropped them, which is why adding
+/// `aliases` and `sync_strategy` did.
+pub const CURRENT_VERSION: u32 = 3;
+
+type Migration = fn(&mut Mapping) -> Result<()>;
+
+// `MIGRATIONS[n]` upgrades a document from version `n + 1` to `n + 2`
+const MIGRATIONS: &[Migration] = &[
+    v1_to_v2,
+    v2_to_v3,
+];
+
+/// Upgrade a raw registry document in place to `CURRENT_VERSION`.
+///
+/// Returns the version the document was stored with.
+pub fn upgrade(doc: &mut Value) -> Result<u32> {
+    let mapping = doc.as_mapping_mut()
+        .ok_or_else(|| ProjectManError::Config("Workspace registry is not a YAML mapping".to_string()))?;
+    
+    let found = detect_version(mapping)?;
+    
+    if found > CURRENT_VERSION {
+        return Err(ProjectManError::RegistryTooNew {
+            found,
+            supported: CURRENT_VERSION,
+        });
+    }
+    
+    for migration in &MIGRATIONS[(found - 1) as usize..] {
+        migration(mapping)?;
+    }
+    
+    Ok(found)
+}
+
+fn detect_version(mapping: &Mapping) -> Result<u32> {
+    match mapping.get("version") {
+        // Registries predating schema numbers used "1.0"
+        Some(Value::String(version)) => version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse().ok())
+            .filter(|major| *major >= 1)
+            .ok_or_else(|| ProjectManError::Config(format!("Unrecognized registry version: {}", version))),
+        Some(Value::Number(version)) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| ProjectManError::Config(format!("Unrecognized registry version: {}", version))),
+        Some(_) => Err(ProjectManError::Config("Registry version must be a number".to_string())),
+        None => Ok(1),
+    }
+}
+
+fn v1_to_v2(mapping: &mut Mapping) -> Result<()> {
+    // v2 switches the free-form "1.0" string to an integer schema number
+    mapping.insert(Value::from("version"), Value::from(2));
+    Ok(())
+}
+
+fn v2_to_v3(mapping: &mut Mapping) -> Result<()> {
+    // v3 adds the optional `aliases` and `sync_strategy` repository fields and keeps unknown
+    // keys; a v2 document needs no changes
+    mapping.insert(Value::from("version"), Value::from(3));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn document(yaml: &str) -> Value {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+    
+    #[test]
+    fn upgrades_legacy_string_version() {
+        let mut doc = document("version: '1.0'\nrepositories: {}");
+        assert_eq!(upgrade(&mut doc).unwrap(), 1);
+        assert_eq!(doc["version"], Value::from(CURRENT_VERSION));
+        assert!(doc["repositories"].is_mapping());
+    }
+    
+    #[test]
+    fn treats_missing_version_as_v1() {
+        let mut doc = document("repositories: {}");
+        assert_eq!(upgrade(&mut doc).unwrap(), 1);
+        assert_eq!(doc["version"], Value::from(CURRENT_VERSION));
+    }
+    
+    #[test]
+    fn leaves_current_version_alone() {
+        let mut doc = document(&format!("version: {}\nrepositories: {{}}", CURRENT_VERSION));
+        assert_eq!(upgrade(&mut doc).unwrap(), CURRENT_VERSION);
+        assert_eq!(doc["version"], Value::from(CURRENT_VERSION));
+    }
+    
+    #[test]
+    fn refuses_newer_versions() {
+        let mut doc = document(&format!("version: {}", CURRENT_VERSION + 1));
+        assert!(matches!(
+            upgrade(&mut doc),
+            Err(ProjectManError::RegistryTooNew { supported: CURRENT_VERSION, .. })
+        ));
+    }
+    
+    #[test]
+    fn rejects_unrecognized_versions() {
+        for yaml in ["version: 0", "version: 'beta'", "version: [1]", "version: -1"] {
+            assert!(upgrade(&mut document(yaml)).is_err(), "{}", yaml);
+        }
+    }
+    
+    #[test]
+    fn has_a_migration_per_version() {
+        assert_eq!(MIGRATIONS.len() as u32, CURRENT_VERSION - 1);
+    }
+}
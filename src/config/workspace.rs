@@ -1,16 +1,24 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use crate::error::{ProjectManError, Result};
-use crate::config::GlobalConfig;
+use crate::config::{GlobalConfig, schema};
+use crate::config::lock::{FileLock, write_atomic};
+use crate::git::SyncStrategy;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorkspaceRegistry {
-    pub version: String,
+    pub version: u32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub repositories: HashMap<String, RepositoryConfig>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+    /// Schema version of the file this was read from, when older than the current one;
+    /// the original is backed up when the upgraded registry first replaces it
+    #[serde(skip)]
+    upgraded_from: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,20 +28,83 @@ pub struct RepositoryConfig {
     pub added_at: DateTime<Utc>,
     pub last_sync: Option<DateTime<Utc>>,
     pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Strategy `p sync` uses for this repository when `--strategy` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_strategy: Option<SyncStrategy>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
 }
 
 impl WorkspaceRegistry {
     pub fn new() -> Self {
         let now = Utc::now();
         Self {
-            version: "1.0".to_string(),
+            version: schema::CURRENT_VERSION,
             created_at: now,
             updated_at: now,
             repositories: HashMap::new(),
+            extra: serde_yaml::Mapping::new(),
+            upgraded_from: None,
         }
     }
     
     pub fn load_from_workspace() -> Result<Self> {
+        let registry_path = Self::registry_path()?;
+        Self::read_from(&registry_path)
+    }
+    
+    /// Apply `f` to the on-disk registry while holding its lock, then save.
+    ///
+    /// Commands that spend a long time between loading and saving (cloning,
+    /// syncing) should record their changes through this so they are merged
+    /// into whatever other `p` processes wrote in the meantime.
+    pub fn update<T>(f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let registry_path = Self::registry_path()?;
+        let _lock = FileLock::acquire(&registry_path)?;
+        
+        let mut registry = Self::read_from(&registry_path)?;
+        let result = f(&mut registry)?;
+        registry.write_to(&registry_path)?;
+        
+        Ok(result)
+    }
+    
+    /// Like `update`, but starts from an empty registry if the file on disk is unreadable.
+    ///
+    /// The unreadable file is moved aside to `project-man.yml.corrupt` first.
+    pub fn rebuild<T>(f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let registry_path = Self::registry_path()?;
+        let _lock = FileLock::acquire(&registry_path)?;
+        
+        let mut registry = match Self::read_from(&registry_path) {
+            Ok(registry) => registry,
+            Err(e @ (ProjectManError::Yaml(_) | ProjectManError::Config(_))) => {
+                let corrupt_path = registry_path.with_file_name("project-man.yml.corrupt");
+                std::fs::rename(&registry_path, &corrupt_path)?;
+                eprintln!("⚠️  Workspace registry is unreadable ({}), moved to {}", e, corrupt_path.display());
+                Self::new()
+            }
+            Err(e) => return Err(e),
+        };
+        let result = f(&mut registry)?;
+        registry.write_to(&registry_path)?;
+        
+        Ok(result)
+    }
+    
+    pub fn save(&mut self) -> Result<()> {
+        let registry_path = Self::registry_path()?;
+        self.save_to(&registry_path)
+    }
+    
+    pub fn save_to(&mut self, registry_path: &Path) -> Result<()> {
+        let _lock = FileLock::acquire(registry_path)?;
+        self.write_to(registry_path)
+    }
+    
+    fn registry_path() -> Result<PathBuf> {
         let global_config = GlobalConfig::load()?;
         let workspace_path = global_config.get_workspace_path();
         
@@ -41,37 +112,54 @@ impl WorkspaceRegistry {
             return Err(ProjectManError::WorkspaceNotFound);
         }
         
-        let registry_path = workspace_path.join("project-man.yml");
-        
+        Ok(workspace_path.join("project-man.yml"))
+    }
+    
+    fn read_from(registry_path: &Path) -> Result<Self> {
         if !registry_path.exists() {
             return Ok(Self::new());
         }
         
-        let content = std::fs::read_to_string(&registry_path)?;
-        let mut registry: WorkspaceRegistry = serde_yaml::from_str(&content)?;
+        let content = std::fs::read_to_string(registry_path)?;
+        let mut document: serde_yaml::Value = serde_yaml::from_str(&content)?;
         
-        // Validate that all repository paths exist
-        registry.repositories.retain(|_, repo| {
-            workspace_path.join(&repo.path).exists()
-        });
+        // Upgraded in memory only: read-only commands must work on a workspace they
+        // can't write to, and the file on disk stays as it is until the next save
+        let stored_version = schema::upgrade(&mut document)?;
         
+        // Entries whose directory is missing are kept on purpose: the disk may just
+        // be unmounted. `p doctor` reports them and offers to re-clone or forget.
+        let mut registry: WorkspaceRegistry = serde_yaml::from_value(document)?;
+        if stored_version < schema::CURRENT_VERSION {
+            registry.upgraded_from = Some(stored_version);
+        }
         Ok(registry)
     }
     
-    pub fn save(&mut self) -> Result<()> {
-        let global_config = GlobalConfig::load()?;
-        let workspace_path = global_config.get_workspace_path();
+    /// Keep a copy of a registry written by an older schema before the upgraded one replaces it.
+    fn backup_original(registry_path: &Path, version: u32) -> Result<()> {
+        let backup_path = registry_path.with_file_name(format!("project-man.yml.v{}.bak", version));
         
-        if !workspace_path.exists() {
-            return Err(ProjectManError::WorkspaceNotFound);
+        if registry_path.exists() && !backup_path.exists() {
+            write_atomic(&backup_path, &std::fs::read_to_string(registry_path)?)?;
         }
+        eprintln!("📦 Upgrading workspace registry from schema v{} to v{} (backup: {})",
+            version, schema::CURRENT_VERSION, backup_path.display());
         
+        Ok(())
+    }
+    
+    fn write_to(&mut self, registry_path: &Path) -> Result<()> {
         self.updated_at = Utc::now();
         
-        let registry_path = workspace_path.join("project-man.yml");
+        if let Some(version) = self.upgraded_from {
+            Self::backup_original(registry_path, version)?;
+        }
+        
         let content = serde_yaml::to_string(self)?;
+        write_atomic(registry_path, &content)?;
+        self.upgraded_from = None;
         
-        std::fs::write(&registry_path, content)?;
         Ok(())
     }
     
@@ -80,6 +168,17 @@ impl WorkspaceRegistry {
         self.updated_at = Utc::now();
     }
     
+    /// `name`, or `name-2`, `name-3`, … if it's taken.
+    ///
+    /// Names come from paths with `/` turned into `_`, so different paths (`a/b_c` and
+    /// `a_b/c`) can ask for the same one.
+    pub fn unique_name(&self, name: &str) -> String {
+        (1..)
+            .map(|n| if n == 1 { name.to_string() } else { format!("{}-{}", name, n) })
+            .find(|candidate| !self.repositories.contains_key(candidate))
+            .unwrap_or_else(|| name.to_string())
+    }
+    
     pub fn remove_repository(&mut self, name: &str) -> Option<RepositoryConfig> {
         let result = self.repositories.remove(name);
         if result.is_some() {
@@ -131,6 +230,131 @@ impl RepositoryConfig {
             added_at: Utc::now(),
             last_sync: None,
             tags,
+            aliases: Vec::new(),
+            sync_strategy: None,
+            extra: serde_yaml::Mapping::new(),
         }
     }
+    
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+    
+    /// Returns `false` if the tag was already present.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        if self.has_tag(tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        true
+    }
+    
+    /// Returns `false` if the tag was not present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != before
+    }
+    
+    /// Returns `false` if the alias was already present.
+    pub fn add_alias(&mut self, alias: &str) -> bool {
+        if self.aliases.iter().any(|a| a == alias) {
+            return false;
+        }
+        self.aliases.push(alias.to_string());
+        true
+    }
+    
+    /// Returns `false` if the alias was not present.
+    pub fn remove_alias(&mut self, alias: &str) -> bool {
+        let before = self.aliases.len();
+        self.aliases.retain(|a| a != alias);
+        self.aliases.len() != before
+    }
+}
+
+/// Tags are single words so they stay usable as `--tag` values and in listings.
+pub fn validate_tag(tag: &str) -> Result<()> {
+    validate_label("tag", tag)
+}
+
+pub fn validate_alias(alias: &str) -> Result<()> {
+    validate_label("alias", alias)
+}
+
+fn validate_label(kind: &str, value: &str) -> Result<()> {
+    if value.is_empty() || value.chars().any(|c| c.is_whitespace() || c == ',') {
+        return Err(ProjectManError::Config(format!(
+            "Invalid {} '{}': it must be non-empty and contain no spaces or commas",
+            kind, value
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn keeps_unknown_keys_when_saving() {
+        let yaml = "\
+version: 3
+created_at: 2026-01-01T00:00:00Z
+updated_at: 2026-01-01T00:00:00Z
+future_setting: true
+repositories:
+  github.com_o_r:
+    path: github.com/o/r
+    url: https://github.com/o/r.git
+    added_at: 2026-01-01T00:00:00Z
+    last_sync: null
+    tags: []
+    future_field: [1, 2]
+";
+        let registry: WorkspaceRegistry = serde_yaml::from_str(yaml).unwrap();
+        let saved: serde_yaml::Value = serde_yaml::to_value(&registry).unwrap();
+        
+        assert_eq!(saved["future_setting"], serde_yaml::Value::from(true));
+        assert_eq!(saved["repositories"]["github.com_o_r"]["future_field"][1], serde_yaml::Value::from(2));
+        assert!(registry.repositories["github.com_o_r"].aliases.is_empty());
+    }
+    
+    #[test]
+    fn picks_a_free_name() {
+        let mut registry = WorkspaceRegistry::new();
+        assert_eq!(registry.unique_name("a_b_c"), "a_b_c");
+        
+        registry.add_repository("a_b_c".to_string(), RepositoryConfig::new("a/b_c".to_string(), String::new(), vec![]));
+        assert_eq!(registry.unique_name("a_b_c"), "a_b_c-2");
+        
+        registry.add_repository("a_b_c-2".to_string(), RepositoryConfig::new("x/y".to_string(), String::new(), vec![]));
+        assert_eq!(registry.unique_name("a_b_c"), "a_b_c-3");
+    }
+    
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("p-workspace-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    #[test]
+    fn backs_up_an_old_registry_only_when_saving() {
+        let dir = scratch_dir("upgrade");
+        let path = dir.join("project-man.yml");
+        let backup_path = dir.join("project-man.yml.v1.bak");
+        let original = "version: \"1.0\"\ncreated_at: 2026-01-01T00:00:00Z\nupdated_at: 2026-01-01T00:00:00Z\nrepositories: {}\n";
+        std::fs::write(&path, original).unwrap();
+        
+        let mut registry = WorkspaceRegistry::read_from(&path).unwrap();
+        assert_eq!(registry.version, schema::CURRENT_VERSION);
+        assert!(!backup_path.exists());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        
+        registry.write_to(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup_path).unwrap(), original);
+        assert!(WorkspaceRegistry::read_from(&path).unwrap().upgraded_from.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
\ No newline at end of file
@@ -14,6 +14,21 @@ pub enum ProjectManError {
     #[error("Workspace not found. Run 'p init <path>' to initialize a workspace")]
     WorkspaceNotFound,
     
+    #[error("Unknown workspace '{0}'. Use 'p workspace list' to see configured workspaces")]
+    UnknownWorkspace(String),
+    
+    #[error("Workspace registry uses schema version {found}, but this version of p only supports up to {supported}. Please upgrade p")]
+    RegistryTooNew { found: u32, supported: u32 },
+    
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    
+    #[error("Several repositories match and there is no terminal to choose on:\n{0}\nPass --first or --select <N> to pick one")]
+    AmbiguousMatch(String),
+    
+    #[error("Shell integration error: {0}")]
+    ShellIntegration(String),
+    
     #[error("Invalid repository URL: {0}")]
     InvalidUrl(String),
     
@@ -26,6 +41,12 @@ pub enum ProjectManError {
     #[error("TOML parsing error: {0}")]
     Toml(#[from] toml::de::Error),
     
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    
 }
 
+/// Exit status for `AmbiguousMatch`, so scripts can tell it apart from other failures.
+pub const AMBIGUOUS_MATCH_EXIT_CODE: i32 = 3;
+
 pub type Result<T> = std::result::Result<T, ProjectManError>;
\ No newline at end of file
@@ -0,0 +1,475 @@
+use std::path::{Path, PathBuf};
+use chrono::DateTime;
+use git2::{Branch, Cred, CredentialType, ErrorCode, FetchOptions, RemoteCallbacks, Repository, Sort, Status, StatusOptions};
+use crate::error::Result;
+use super::{CommitSummary, RepoStatus, git_failure, run_git};
+
+const STAGED: Status = Status::INDEX_NEW
+    .union(Status::INDEX_MODIFIED)
+    .union(Status::INDEX_DELETED)
+    .union(Status::INDEX_RENAMED)
+    .union(Status::INDEX_TYPECHANGE);
+const MODIFIED: Status = Status::WT_MODIFIED
+    .union(Status::WT_DELETED)
+    .union(Status::WT_RENAMED)
+    .union(Status::WT_TYPECHANGE);
+
+/// The git operations `p` runs on many repositories at once (`list`, `status`, `sync`).
+///
+/// `GitManager` goes through a backend for these so they can be served by a library
+/// instead of one `git` process each, and so a fake can stand in for real repositories.
+pub trait GitBackend: Send + Sync {
+    fn status(&self, repo_path: &Path) -> Result<RepoStatus>;
+    
+    /// Commits on HEAD missing from its upstream, and the other way round.
+    ///
+    /// `None` when HEAD is detached or the branch has no upstream.
+    fn ahead_behind(&self, repo_path: &Path) -> Result<Option<(usize, usize)>>;
+    
+    /// Name of the checked-out branch, or `None` when HEAD is detached.
+    fn current_branch(&self, repo_path: &Path) -> Result<Option<String>>;
+    
+    /// Whether the checked-out branch tracks an upstream that has been fetched.
+    fn has_upstream(&self, repo_path: &Path) -> Result<bool>;
+    
+    /// One-line summaries (`<short hash> <subject>`) of the newest `count` commits on HEAD.
+    fn recent_commits(&self, repo_path: &Path, count: usize) -> Result<Vec<String>>;
+    
+    /// URL of the `origin` remote, or `None` when the repository has no origin.
+    fn remote_url(&self, repo_path: &Path) -> Result<Option<String>>;
+    
+    /// Fetch the remote the current branch tracks, or `origin`.
+    fn fetch(&self, repo_path: &Path) -> Result<()>;
+}
+
+/// Runs the `git` command line for everything.
+pub struct SubprocessBackend;
+
+impl GitBackend for SubprocessBackend {
+    fn status(&self, repo_path: &Path) -> Result<RepoStatus> {
+        // The v2 format is meant for scripts: stable across git versions and not translated
+        let output = run_git(repo_path, &["status", "--porcelain=v2", "--branch"])?;
+        if !output.status.success() {
+            return Err(git_failure("status", &output.stderr));
+        }
+        
+        let mut status = parse_porcelain_v2(&String::from_utf8_lossy(&output.stdout));
+        
+        // No stash ref just means no stashes
+        let stashes = run_git(repo_path, &["rev-list", "--walk-reflogs", "--count", "refs/stash"])?;
+        if stashes.status.success() {
+            status.stashes = String::from_utf8_lossy(&stashes.stdout).trim().parse().unwrap_or(0);
+        }
+        
+        let log = run_git(repo_path, &["log", "-1", "--format=%h%x00%ct%x00%s"])?;
+        if log.status.success() {
+            let log = String::from_utf8_lossy(&log.stdout);
+            let mut fields = log.trim_end_matches('\n').splitn(3, '\0');
+            if let (Some(short_id), Some(timestamp), Some(subject)) = (fields.next(), fields.next(), fields.next()) {
+                status.last_commit = Some(CommitSummary {
+                    short_id: short_id.to_string(),
+                    date: timestamp.parse().ok().and_then(|seconds| DateTime::from_timestamp(seconds, 0)).unwrap_or_default(),
+                    subject: subject.to_string(),
+                });
+            }
+        }
+        
+        Ok(status)
+    }
+    
+    fn ahead_behind(&self, repo_path: &Path) -> Result<Option<(usize, usize)>> {
+        let output = run_git(repo_path, &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"])?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        
+        let result = String::from_utf8_lossy(&output.stdout);
+        let counts = result.trim().split_once('\t')
+            .and_then(|(ahead, behind)| Some((ahead.parse().ok()?, behind.parse().ok()?)));
+        Ok(counts)
+    }
+    
+    fn current_branch(&self, repo_path: &Path) -> Result<Option<String>> {
+        let output = run_git(repo_path, &["symbolic-ref", "--short", "-q", "HEAD"])?;
+        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(if output.status.success() && !branch.is_empty() { Some(branch) } else { None })
+    }
+    
+    fn has_upstream(&self, repo_path: &Path) -> Result<bool> {
+        let output = run_git(repo_path, &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{upstream}"])?;
+        Ok(output.status.success())
+    }
+    
+    fn recent_commits(&self, repo_path: &Path, count: usize) -> Result<Vec<String>> {
+        let count = count.to_string();
+        let output = run_git(repo_path, &["log", "--format=%h %s", "-n", &count])?;
+        
+        // An unborn branch has no log; that's not an error worth surfacing
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+        
+        Ok(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+    }
+    
+    fn remote_url(&self, repo_path: &Path) -> Result<Option<String>> {
+        let output = run_git(repo_path, &["remote", "get-url", "origin"])?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(if url.is_empty() { None } else { Some(url) })
+    }
+    
+    fn fetch(&self, repo_path: &Path) -> Result<()> {
+        let output = run_git(repo_path, &["fetch", "--quiet"])?;
+        if !output.status.success() {
+            return Err(git_failure("fetch", &output.stderr));
+        }
+        Ok(())
+    }
+}
+
+/// Reads repositories in-process through libgit2: no process per call, and no parsing of
+/// output that changes with the git version or locale.
+///
+/// Anything libgit2 can't handle (an unsupported repository format, SSH host aliases,
+/// credential setups it doesn't know) is retried with the `git` command line.
+pub struct LibgitBackend {
+    ssh_key_path: Option<PathBuf>,
+    fallback: SubprocessBackend,
+}
+
+impl LibgitBackend {
+    pub fn new(ssh_key_path: Option<PathBuf>) -> Self {
+        Self {
+            ssh_key_path,
+            fallback: SubprocessBackend,
+        }
+    }
+    
+    fn try_status(&self, repo_path: &Path) -> std::result::Result<RepoStatus, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let mut status = RepoStatus::default();
+        
+        // Same set of files as `git status`: untracked ones count, ignored ones don't
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).include_ignored(false);
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            let flags = entry.status();
+            if flags.is_conflicted() {
+                status.conflicted += 1;
+            } else if flags.is_wt_new() {
+                status.untracked += 1;
+            } else {
+                if flags.intersects(STAGED) {
+                    status.staged += 1;
+                }
+                if flags.intersects(MODIFIED) {
+                    status.modified += 1;
+                }
+            }
+        }
+        
+        status.branch = checked_out_branch(&repo)?;
+        if let Some(branch) = &status.branch {
+            status.upstream = repo.branch_upstream_name(&format!("refs/heads/{}", branch))
+                .ok()
+                .and_then(|name| name.as_str().map(shorten_ref));
+        }
+        
+        (status.ahead, status.behind) = upstream_counts(&repo)?.unwrap_or((0, 0));
+        status.stashes = repo.reflog("refs/stash").map(|reflog| reflog.len()).unwrap_or(0);
+        
+        status.last_commit = match repo.head() {
+            Ok(head) => {
+                let commit = head.peel_to_commit()?;
+                let short_id = commit.as_object().short_id()?;
+                Some(CommitSummary {
+                    short_id: short_id.as_str().unwrap_or_default().to_string(),
+                    date: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default(),
+                    subject: commit.summary().unwrap_or_default().to_string(),
+                })
+            }
+            Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(e),
+        };
+        
+        Ok(status)
+    }
+    
+    fn try_has_upstream(&self, repo_path: &Path) -> std::result::Result<bool, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let Some(branch) = checked_out_branch(&repo)? else {
+            return Ok(false);
+        };
+        
+        // Like `@{upstream}`: configured, and its remote-tracking branch exists
+        let upstream = match repo.branch_upstream_name(&format!("refs/heads/{}", branch)) {
+            Ok(upstream) => upstream,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let Some(upstream) = upstream.as_str() else {
+            return Ok(false);
+        };
+        match repo.find_reference(upstream).map(|_| ()) {
+            Ok(()) => Ok(true),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    
+    fn try_recent_commits(&self, repo_path: &Path, count: usize) -> std::result::Result<Vec<String>, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let mut revwalk = repo.revwalk()?;
+        match revwalk.push_head() {
+            Ok(()) => {}
+            Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        }
+        // Newest first, as `git log` lists them
+        revwalk.set_sorting(Sort::TIME)?;
+        
+        revwalk
+            .take(count)
+            .map(|id| {
+                let commit = repo.find_commit(id?)?;
+                let short_id = commit.as_object().short_id()?;
+                Ok(format!("{} {}", short_id.as_str().unwrap_or_default(), commit.summary().unwrap_or_default()))
+            })
+            .collect()
+    }
+    
+    fn try_remote_url(&self, repo_path: &Path) -> std::result::Result<Option<String>, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let remote = match repo.find_remote("origin") {
+            Ok(remote) => remote,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        
+        let url = remote.url()
+            .ok_or_else(|| git2::Error::from_str("remote URL is not valid UTF-8"))?;
+        Ok(if url.is_empty() { None } else { Some(url.to_string()) })
+    }
+    
+    fn try_fetch(&self, repo_path: &Path) -> std::result::Result<(), git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let remote_name = tracked_remote(&repo).unwrap_or_else(|| "origin".to_string());
+        let mut remote = repo.find_remote(&remote_name)?;
+        
+        let config = repo.config()?;
+        let ssh_key_path = self.ssh_key_path.clone();
+        let mut ssh_attempts = 0;
+        let mut asked_helper = false;
+        let mut used_default = false;
+        
+        let mut callbacks = RemoteCallbacks::new();
+        // libgit2 calls this again after each rejected credential, so each source is tried
+        // once before giving up
+        callbacks.credentials(move |url, username, allowed| {
+            if allowed.contains(CredentialType::USERNAME) {
+                Cred::username(username.unwrap_or("git"))
+            } else if allowed.contains(CredentialType::SSH_KEY) {
+                ssh_attempts += 1;
+                let username = username.unwrap_or("git");
+                match (ssh_attempts, &ssh_key_path) {
+                    (1, _) => Cred::ssh_key_from_agent(username),
+                    (2, Some(key)) => Cred::ssh_key(username, None, key, None),
+                    _ => Err(git2::Error::from_str("no SSH key was accepted")),
+                }
+            } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !asked_helper {
+                asked_helper = true;
+                Cred::credential_helper(&config, url, username)
+            } else if allowed.contains(CredentialType::DEFAULT) && !used_default {
+                used_default = true;
+                Cred::default()
+            } else {
+                Err(git2::Error::from_str("authentication failed"))
+            }
+        });
+        
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        
+        // No refspecs: use the ones configured for the remote, like `git fetch`
+        remote.fetch(&[] as &[&str], Some(&mut options), None)
+    }
+}
+
+impl GitBackend for LibgitBackend {
+    fn status(&self, repo_path: &Path) -> Result<RepoStatus> {
+        self.try_status(repo_path)
+            .or_else(|_| self.fallback.status(repo_path))
+    }
+    
+    fn ahead_behind(&self, repo_path: &Path) -> Result<Option<(usize, usize)>> {
+        Repository::open(repo_path)
+            .and_then(|repo| upstream_counts(&repo))
+            .or_else(|_| self.fallback.ahead_behind(repo_path))
+    }
+    
+    fn current_branch(&self, repo_path: &Path) -> Result<Option<String>> {
+        Repository::open(repo_path)
+            .and_then(|repo| checked_out_branch(&repo))
+            .or_else(|_| self.fallback.current_branch(repo_path))
+    }
+    
+    fn has_upstream(&self, repo_path: &Path) -> Result<bool> {
+        self.try_has_upstream(repo_path)
+            .or_else(|_| self.fallback.has_upstream(repo_path))
+    }
+    
+    fn recent_commits(&self, repo_path: &Path, count: usize) -> Result<Vec<String>> {
+        self.try_recent_commits(repo_path, count)
+            .or_else(|_| self.fallback.recent_commits(repo_path, count))
+    }
+    
+    fn remote_url(&self, repo_path: &Path) -> Result<Option<String>> {
+        self.try_remote_url(repo_path)
+            .or_else(|_| self.fallback.remote_url(repo_path))
+    }
+    
+    fn fetch(&self, repo_path: &Path) -> Result<()> {
+        self.try_fetch(repo_path)
+            .or_else(|_| self.fallback.fetch(repo_path))
+    }
+}
+
+/// Branch and file counts from `git status --porcelain=v2 --branch`.
+fn parse_porcelain_v2(output: &str) -> RepoStatus {
+    let mut status = RepoStatus::default();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            match header.split_once(' ') {
+                Some(("branch.head", head)) if head != "(detached)" => status.branch = Some(head.to_string()),
+                Some(("branch.upstream", upstream)) => status.upstream = Some(upstream.to_string()),
+                Some(("branch.ab", counts)) => {
+                    // `+<ahead> -<behind>`
+                    let mut counts = counts.split(' ')
+                        .map(|count| count.trim_start_matches(['+', '-']).parse().unwrap_or(0));
+                    status.ahead = counts.next().unwrap_or(0);
+                    status.behind = counts.next().unwrap_or(0);
+                }
+                _ => {}
+            }
+            continue;
+        }
+        
+        match line.split(' ').next() {
+            // Ordinary and renamed entries: `1 XY ...` / `2 XY ...`, `.` meaning unchanged
+            Some("1" | "2") => {
+                let mut xy = line[2..].chars();
+                if xy.next().is_some_and(|x| x != '.') {
+                    status.staged += 1;
+                }
+                if xy.next().is_some_and(|y| y != '.') {
+                    status.modified += 1;
+                }
+            }
+            Some("u") => status.conflicted += 1,
+            Some("?") => status.untracked += 1,
+            _ => {}
+        }
+    }
+    
+    status
+}
+
+/// Name of the checked-out branch, `None` when HEAD is detached.
+fn checked_out_branch(repo: &Repository) -> std::result::Result<Option<String>, git2::Error> {
+    // HEAD names a branch even before the first commit; only a detached HEAD doesn't
+    let head = repo.find_reference("HEAD")?;
+    Ok(head.symbolic_target()
+        .and_then(|target| target.strip_prefix("refs/heads/"))
+        .map(str::to_string))
+}
+
+/// Ahead/behind counts of the checked-out branch against its upstream.
+fn upstream_counts(repo: &Repository) -> std::result::Result<Option<(usize, usize)>, git2::Error> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        // Unborn branch: nothing to compare yet
+        Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    
+    let Some(local) = head.target() else {
+        return Ok(None);
+    };
+    let upstream = match Branch::wrap(head).upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let Some(upstream) = upstream.get().target() else {
+        return Ok(None);
+    };
+    
+    repo.graph_ahead_behind(local, upstream).map(Some)
+}
+
+/// `refs/remotes/origin/main` -> `origin/main`, as git shows upstreams.
+fn shorten_ref(name: &str) -> String {
+    name.strip_prefix("refs/remotes/")
+        .or_else(|| name.strip_prefix("refs/heads/"))
+        .unwrap_or(name)
+        .to_string()
+}
+
+/// Remote configured as the upstream of the checked-out branch.
+fn tracked_remote(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    let refname = head.name()?;
+    let remote = repo.branch_upstream_remote(refname).ok()?;
+    remote.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_branch_headers() {
+        let status = parse_porcelain_v2(
+            "# branch.oid 4f1c0de\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +2 -5\n",
+        );
+        
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 5));
+        assert!(status.is_clean());
+    }
+    
+    #[test]
+    fn detached_head_has_no_branch() {
+        let status = parse_porcelain_v2("# branch.oid 4f1c0de\n# branch.head (detached)\n");
+        
+        assert_eq!(status.branch, None);
+        assert_eq!(status.upstream, None);
+    }
+    
+    #[test]
+    fn counts_entries_by_kind() {
+        let status = parse_porcelain_v2(concat!(
+            "# branch.head main\n",
+            "1 M. N... 100644 100644 100644 aaa bbb staged.rs\n",
+            "1 .M N... 100644 100644 100644 aaa bbb modified.rs\n",
+            "1 MM N... 100644 100644 100644 aaa bbb both.rs\n",
+            "2 R. N... 100644 100644 100644 aaa bbb R100 new name.rs\told.rs\n",
+            "u UU N... 100644 100644 100644 100644 aaa bbb ccc conflicted.rs\n",
+            "? untracked file.txt\n",
+            "! ignored.log\n",
+        ));
+        
+        assert_eq!(status.staged, 3);
+        assert_eq!(status.modified, 2);
+        assert_eq!(status.conflicted, 1);
+        assert_eq!(status.untracked, 1);
+    }
+}
\ No newline at end of file
@@ -1,17 +1,38 @@
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use url::Url;
 use crate::error::{ProjectManError, Result};
 use crate::config::GlobalConfig;
 
+mod backend;
+
+pub use backend::GitBackend;
+use backend::{LibgitBackend, SubprocessBackend};
+
+/// How many new commit summaries a `SyncResult` carries at most.
+pub const CHANGELOG_LIMIT: usize = 20;
+
 pub struct GitManager {
     config: GlobalConfig,
+    backend: Box<dyn GitBackend>,
 }
 
 impl GitManager {
     pub fn new() -> Result<Self> {
         let config = GlobalConfig::load()?;
-        Ok(Self { config })
+        let backend: Box<dyn GitBackend> = match config.git.backend.as_str() {
+            "subprocess" => Box::new(SubprocessBackend),
+            _ => Box::new(LibgitBackend::new(config.git.ssh_key_path.clone())),
+        };
+        Ok(Self::with_backend(config, backend))
+    }
+    
+    pub fn with_backend(config: GlobalConfig, backend: Box<dyn GitBackend>) -> Self {
+        Self { config, backend }
     }
     
     pub fn clone_repository(&self, url: &str, target_path: &Path) -> Result<()> {
@@ -47,79 +68,184 @@ impl GitManager {
         }
     }
     
-    pub fn sync_repository(&self, repo_path: &Path) -> Result<SyncResult> {
-        // Use git pull command directly
-        let output = Command::new("git")
-            .arg("pull")
-            .arg("--ff-only")
-            .current_dir(repo_path)
-            .output()
-            .map_err(|e| ProjectManError::Git(format!("Failed to execute git pull: {}", e)))?;
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        
-        if output.status.success() {
-            if stdout.contains("Already up to date") {
-                Ok(SyncResult::UpToDate)
-            } else {
-                // Count commits pulled
-                let commits_pulled = stdout.lines()
-                    .filter(|line| line.contains("->") && line.contains("/"))
-                    .count();
-                Ok(SyncResult::Updated { commits_pulled })
+    /// Bring the checked-out branch up to date with its upstream using `strategy`.
+    ///
+    /// Repositories that can't be synced safely (detached HEAD, no upstream, local changes
+    /// in the way) are reported as `Skipped` and left untouched.
+    pub fn sync_repository(&self, repo_path: &Path, strategy: SyncStrategy) -> Result<SyncResult> {
+        let Some(branch) = self.get_current_branch(repo_path)? else {
+            return Ok(SyncResult::Skipped(SkipReason::DetachedHead));
+        };
+        if !self.has_upstream(repo_path)? {
+            return Ok(SyncResult::Skipped(SkipReason::NoUpstream { branch }));
+        }
+        
+        self.backend.fetch(repo_path)?;
+        
+        let (ahead, behind) = self.count_ahead_behind(repo_path)?;
+        let before = self.get_head_commit(repo_path)?;
+        // Upstream commits the old HEAD didn't have: what a successful sync brings in
+        let incoming = match &before {
+            Some(before) => format!("{}..@{{upstream}}", before),
+            None => "@{upstream}".to_string(),
+        };
+        
+        match strategy {
+            SyncStrategy::Fetch if behind == 0 => Ok(SyncResult::UpToDate),
+            SyncStrategy::Fetch => {
+                let (behind, new_commits) = self.get_commits_in(repo_path, &incoming)?;
+                Ok(SyncResult::Fetched { behind, new_commits })
+            }
+            _ if behind == 0 => Ok(SyncResult::UpToDate),
+            SyncStrategy::FfOnly if ahead > 0 => Ok(SyncResult::Conflict { ahead, behind }),
+            SyncStrategy::FfOnly => {
+                let merge = run_git(repo_path, &["merge", "--ff-only", "@{upstream}"])?;
+                if merge.status.success() {
+                    if self.get_head_commit(repo_path)? == before {
+                        return Ok(SyncResult::UpToDate);
+                    }
+                    let (commits_pulled, new_commits) = self.get_commits_in(repo_path, &incoming)?;
+                    Ok(SyncResult::Updated { commits_pulled, new_commits })
+                } else if self.has_local_changes(repo_path, true)? {
+                    // Fast-forwarding only fails when local files are in the way
+                    Ok(SyncResult::Skipped(SkipReason::LocalChanges))
+                } else {
+                    Err(git_failure("merge", &merge.stderr))
+                }
+            }
+            SyncStrategy::Rebase | SyncStrategy::RebaseAutostash => {
+                let autostash = strategy == SyncStrategy::RebaseAutostash;
+                if !autostash && self.has_local_changes(repo_path, false)? {
+                    return Ok(SyncResult::Skipped(SkipReason::LocalChanges));
+                }
+                
+                let rebase_args: &[&str] = if autostash {
+                    &["rebase", "--autostash", "@{upstream}"]
+                } else {
+                    &["rebase", "@{upstream}"]
+                };
+                let rebase = run_git(repo_path, rebase_args)?;
+                
+                if !rebase.status.success() {
+                    // A rebase stopped on conflicts is left in progress; aborting it also restores
+                    // an autostash. Anything else is a plain failure
+                    if !self.rebase_in_progress(repo_path)? {
+                        return Err(git_failure("rebase", &rebase.stderr));
+                    }
+                    let abort = run_git(repo_path, &["rebase", "--abort"])?;
+                    return if abort.status.success() {
+                        Ok(SyncResult::RebaseConflict { ahead, behind })
+                    } else {
+                        Err(git_failure("rebase --abort", &abort.stderr))
+                    };
+                }
+                
+                if self.get_head_commit(repo_path)? == before {
+                    return Ok(SyncResult::UpToDate);
+                }
+                let (commits_pulled, new_commits) = self.get_commits_in(repo_path, &incoming)?;
+                
+                // The rebase itself went through, but the stashed changes didn't apply cleanly;
+                // git leaves the conflicted files unmerged and keeps the changes in the stash list
+                if autostash && self.has_unmerged_paths(repo_path)? {
+                    return Ok(SyncResult::AutostashConflict { commits_pulled, new_commits });
+                }
+                
+                let (local_commits, _) = self.count_ahead_behind(repo_path)?;
+                if local_commits == 0 {
+                    Ok(SyncResult::Updated { commits_pulled, new_commits })
+                } else {
+                    Ok(SyncResult::Rebased { commits_pulled, local_commits, new_commits })
+                }
             }
-        } else if stderr.contains("diverged") || stderr.contains("non-fast-forward") {
-            // Get ahead/behind info
-            Ok(SyncResult::Conflict { ahead: 0, behind: 0 }) // We'll get exact numbers later if needed
-        } else {
-            Err(ProjectManError::Git(format!("Git pull failed: {}", stderr)))
         }
     }
     
     pub fn get_repository_status(&self, repo_path: &Path) -> Result<RepoStatus> {
-        // Check if working directory is clean
-        let status_output = Command::new("git")
-            .arg("status")
-            .arg("--porcelain")
-            .current_dir(repo_path)
-            .output()
-            .map_err(|e| ProjectManError::Git(format!("Failed to get git status: {}", e)))?;
-        
-        let is_clean = status_output.stdout.is_empty();
-        
-        // Check ahead/behind status
-        let ahead_behind_output = Command::new("git")
-            .arg("rev-list")
-            .arg("--left-right")
-            .arg("--count")
-            .arg("HEAD...@{upstream}")
-            .current_dir(repo_path)
-            .output();
-        
-        let (ahead, behind) = if let Ok(output) = ahead_behind_output {
-            if output.status.success() {
-                let result = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<&str> = result.trim().split('\t').collect();
-                if parts.len() == 2 {
-                    let ahead = parts[0].parse().unwrap_or(0);
-                    let behind = parts[1].parse().unwrap_or(0);
-                    (ahead, behind)
-                } else {
-                    (0, 0)
-                }
-            } else {
-                (0, 0)
-            }
-        } else {
-            (0, 0)
-        };
+        self.backend.status(repo_path)
+    }
+    
+    /// Commits on HEAD missing from its upstream, and the other way round.
+    ///
+    /// Only asked once the branch is known to track an upstream, so not getting counts is
+    /// an error rather than "nothing to pull".
+    fn count_ahead_behind(&self, repo_path: &Path) -> Result<(usize, usize)> {
+        self.backend.ahead_behind(repo_path)?
+            .ok_or_else(|| ProjectManError::Git("could not compare HEAD with its upstream".to_string()))
+    }
+    
+    /// Hash of the commit HEAD points at, or `None` on an unborn branch.
+    fn get_head_commit(&self, repo_path: &Path) -> Result<Option<String>> {
+        let output = run_git(repo_path, &["rev-parse", "--verify", "-q", "HEAD"])?;
+        let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(if output.status.success() && !commit.is_empty() { Some(commit) } else { None })
+    }
+    
+    /// Exact number of commits in `range`, with one-line summaries of the newest
+    /// `CHANGELOG_LIMIT` of them.
+    fn get_commits_in(&self, repo_path: &Path, range: &str) -> Result<(usize, Vec<String>)> {
+        let count = run_git(repo_path, &["rev-list", "--count", range])?;
+        if !count.status.success() {
+            return Err(git_failure("rev-list", &count.stderr));
+        }
+        let count = String::from_utf8_lossy(&count.stdout).trim().parse().unwrap_or(0);
+        
+        let limit = CHANGELOG_LIMIT.to_string();
+        let log = run_git(repo_path, &["log", "--format=%h %s", "-n", &limit, range])?;
+        let subjects = String::from_utf8_lossy(&log.stdout).lines().map(str::to_string).collect();
         
-        Ok(RepoStatus {
-            is_clean,
-            ahead,
-            behind,
-        })
+        Ok((count, subjects))
+    }
+    
+    fn has_upstream(&self, repo_path: &Path) -> Result<bool> {
+        self.backend.has_upstream(repo_path)
+    }
+    
+    /// Whether tracked files have staged or unstaged changes, and optionally whether there
+    /// are untracked files.
+    fn has_local_changes(&self, repo_path: &Path, untracked: bool) -> Result<bool> {
+        let untracked = if untracked { "--untracked-files=normal" } else { "--untracked-files=no" };
+        let output = run_git(repo_path, &["status", "--porcelain", untracked])?;
+        if !output.status.success() {
+            return Err(git_failure("status", &output.stderr));
+        }
+        Ok(!output.stdout.is_empty())
+    }
+    
+    /// Whether the index has conflicted (unmerged) entries.
+    fn has_unmerged_paths(&self, repo_path: &Path) -> Result<bool> {
+        let output = run_git(repo_path, &["ls-files", "--unmerged"])?;
+        if !output.status.success() {
+            return Err(git_failure("ls-files", &output.stderr));
+        }
+        Ok(!output.stdout.is_empty())
+    }
+    
+    /// Whether a rebase stopped halfway, waiting for conflicts to be resolved.
+    fn rebase_in_progress(&self, repo_path: &Path) -> Result<bool> {
+        for state_dir in ["rebase-merge", "rebase-apply"] {
+            let output = run_git(repo_path, &["rev-parse", "--git-path", state_dir])?;
+            let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+            if output.status.success() && repo_path.join(path).is_dir() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    
+    /// URL of the `origin` remote, or `None` when the repository has no origin.
+    pub fn get_remote_url(&self, repo_path: &Path) -> Result<Option<String>> {
+        self.backend.remote_url(repo_path)
+    }
+    
+    /// Name of the checked-out branch, or `None` when HEAD is detached.
+    pub fn get_current_branch(&self, repo_path: &Path) -> Result<Option<String>> {
+        self.backend.current_branch(repo_path)
+    }
+    
+    /// One-line summaries (`<short hash> <subject>`) of the newest `count` commits on HEAD.
+    pub fn get_recent_commits(&self, repo_path: &Path, count: usize) -> Result<Vec<String>> {
+        self.backend.recent_commits(repo_path, count)
     }
     
     pub fn parse_repository_url(&self, input: &str) -> Result<(String, String)> {
@@ -188,16 +314,252 @@ impl GitManager {
     }
 }
 
+/// Run a non-interactive git command in `repo_path`, capturing its output.
+fn run_git(repo_path: &Path, args: &[&str]) -> Result<std::process::Output> {
+    // Syncs run in parallel, so a credential prompt would interleave with the others; fail instead.
+    // Messages stay in English so the `fatal:`/`error:` lines of `git_failure` are found
+    Command::new("git")
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| ProjectManError::Git(format!("Failed to execute git {}: {}", args[0], e)))
+}
+
+/// Error for a failed git command, keeping git's own `fatal:`/`error:` lines rather than all of stderr.
+fn git_failure(action: &str, stderr: &[u8]) -> ProjectManError {
+    let stderr = String::from_utf8_lossy(stderr);
+    let reasons: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("fatal:") || line.starts_with("error:"))
+        .collect();
+    
+    let reason = if reasons.is_empty() {
+        stderr.lines().map(str::trim).rfind(|line| !line.is_empty()).unwrap_or("unknown error").to_string()
+    } else {
+        reasons.join("; ")
+    };
+    
+    ProjectManError::Git(format!("git {}: {}", action, reason))
+}
+
+/// Find git working trees under `dir`, without descending into repositories or hidden directories.
+pub fn find_git_repositories(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut repositories = Vec::new();
+    
+    fn scan_directory(dir: &Path, repositories: &mut Vec<PathBuf>) -> Result<()> {
+        if dir.join(".git").exists() {
+            repositories.push(dir.to_path_buf());
+            return Ok(()); // Don't recurse into git repos
+        }
+        
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() && !path.file_name().unwrap_or_default().to_string_lossy().starts_with('.') {
+                    let _ = scan_directory(&path, repositories);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    scan_directory(dir, &mut repositories)?;
+    Ok(repositories)
+}
+
+/// How `p sync` brings a branch up to date with its upstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStrategy {
+    /// Only fetch; never touch the working tree
+    Fetch,
+    /// Fast-forward when there are no local commits
+    #[default]
+    FfOnly,
+    /// Rebase local commits onto the upstream; requires a clean working tree
+    Rebase,
+    /// Like `rebase`, stashing local changes around it
+    RebaseAutostash,
+}
+
+impl fmt::Display for SyncStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Same spelling as on the command line, e.g. `ff-only`
+        let value = self.to_possible_value().expect("no strategy is hidden");
+        f.write_str(value.get_name())
+    }
+}
+
+/// Outcome of syncing one repository.
+///
+/// `new_commits` holds `<short hash> <subject>` lines for the upstream commits brought in,
+/// newest first and at most `CHANGELOG_LIMIT` of them; the counts are exact.
 #[derive(Debug)]
 pub enum SyncResult {
     UpToDate,
-    Updated { commits_pulled: usize },
+    /// `fetch` strategy: the upstream has new commits that weren't merged
+    Fetched { behind: usize, new_commits: Vec<String> },
+    Updated { commits_pulled: usize, new_commits: Vec<String> },
+    Rebased { commits_pulled: usize, local_commits: usize, new_commits: Vec<String> },
+    /// `ff-only` strategy: the branch has diverged from its upstream
     Conflict { ahead: usize, behind: usize },
+    /// The rebase stopped on conflicts and was aborted
+    RebaseConflict { ahead: usize, behind: usize },
+    /// Rebased, but the stashed local changes conflict and were left in the stash
+    AutostashConflict { commits_pulled: usize, new_commits: Vec<String> },
+    Skipped(SkipReason),
 }
 
 #[derive(Debug)]
+pub enum SkipReason {
+    DetachedHead,
+    NoUpstream { branch: String },
+    LocalChanges,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct RepoStatus {
-    pub is_clean: bool,
+    /// Checked-out branch, `None` when HEAD is detached
+    pub branch: Option<String>,
+    /// Upstream of the branch, e.g. `origin/main`; `ahead` and `behind` are 0 without one
+    pub upstream: Option<String>,
     pub ahead: usize,
     pub behind: usize,
+    /// Files with changes in the index
+    pub staged: usize,
+    /// Tracked files with changes not yet staged
+    pub modified: usize,
+    pub untracked: usize,
+    /// Files with unresolved merge conflicts; not counted as staged or modified
+    pub conflicted: usize,
+    pub stashes: usize,
+    /// `None` on a branch without commits yet
+    pub last_commit: Option<CommitSummary>,
+}
+
+impl RepoStatus {
+    pub fn is_clean(&self) -> bool {
+        self.staged == 0 && self.modified == 0 && self.untracked == 0 && self.conflicted == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitSummary {
+    pub short_id: String,
+    pub date: DateTime<Utc>,
+    pub subject: String,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    
+    /// Serves canned answers instead of looking at repositories on disk.
+    #[derive(Default)]
+    pub(crate) struct FakeBackend {
+        pub statuses: HashMap<PathBuf, RepoStatus>,
+        pub remote_urls: HashMap<PathBuf, String>,
+        pub commits: HashMap<PathBuf, Vec<String>>,
+    }
+    
+    impl GitBackend for FakeBackend {
+        fn status(&self, repo_path: &Path) -> Result<RepoStatus> {
+            self.statuses.get(repo_path)
+                .cloned()
+                .ok_or_else(|| ProjectManError::Git(format!("not a repository: {}", repo_path.display())))
+        }
+        
+        fn ahead_behind(&self, repo_path: &Path) -> Result<Option<(usize, usize)>> {
+            let status = self.status(repo_path)?;
+            Ok(status.upstream.map(|_| (status.ahead, status.behind)))
+        }
+        
+        fn current_branch(&self, repo_path: &Path) -> Result<Option<String>> {
+            Ok(self.status(repo_path)?.branch)
+        }
+        
+        fn has_upstream(&self, repo_path: &Path) -> Result<bool> {
+            Ok(self.status(repo_path)?.upstream.is_some())
+        }
+        
+        fn recent_commits(&self, repo_path: &Path, count: usize) -> Result<Vec<String>> {
+            let commits = self.commits.get(repo_path).map(Vec::as_slice).unwrap_or_default();
+            Ok(commits.iter().take(count).cloned().collect())
+        }
+        
+        fn remote_url(&self, repo_path: &Path) -> Result<Option<String>> {
+            Ok(self.remote_urls.get(repo_path).cloned())
+        }
+        
+        fn fetch(&self, _repo_path: &Path) -> Result<()> {
+            Ok(())
+        }
+    }
+    
+    pub(crate) fn fake_manager(backend: FakeBackend) -> GitManager {
+        let config = GlobalConfig::new("test", std::env::temp_dir());
+        GitManager::with_backend(config, Box::new(backend))
+    }
+    
+    #[test]
+    fn status_and_remote_come_from_the_backend() {
+        let path = PathBuf::from("/ws/github.com/acme/api");
+        let mut backend = FakeBackend::default();
+        backend.statuses.insert(path.clone(), RepoStatus { branch: Some("main".to_string()), modified: 1, ..Default::default() });
+        backend.remote_urls.insert(path.clone(), "https://github.com/acme/api.git".to_string());
+        let git_manager = fake_manager(backend);
+        
+        let status = git_manager.get_repository_status(&path).unwrap();
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert!(!status.is_clean());
+        assert_eq!(git_manager.get_remote_url(&path).unwrap().as_deref(), Some("https://github.com/acme/api.git"));
+        
+        let elsewhere = Path::new("/ws/github.com/acme/web");
+        assert!(git_manager.get_repository_status(elsewhere).is_err());
+        assert_eq!(git_manager.get_remote_url(elsewhere).unwrap(), None);
+    }
+    
+    #[test]
+    fn ahead_behind_needs_an_upstream() {
+        let tracked = PathBuf::from("/ws/tracked");
+        let local = PathBuf::from("/ws/local");
+        let mut backend = FakeBackend::default();
+        backend.statuses.insert(tracked.clone(), RepoStatus { upstream: Some("origin/main".to_string()), ahead: 1, behind: 3, ..Default::default() });
+        backend.statuses.insert(local.clone(), RepoStatus::default());
+        let git_manager = fake_manager(backend);
+        
+        assert_eq!(git_manager.count_ahead_behind(&tracked).unwrap(), (1, 3));
+        assert!(matches!(git_manager.count_ahead_behind(&local), Err(ProjectManError::Git(_))));
+    }
+    
+    #[test]
+    fn sync_skips_detached_heads_and_branches_without_upstream() {
+        let detached = PathBuf::from("/ws/detached");
+        let local = PathBuf::from("/ws/local");
+        let mut backend = FakeBackend::default();
+        backend.statuses.insert(detached.clone(), RepoStatus::default());
+        backend.statuses.insert(local.clone(), RepoStatus { branch: Some("topic".to_string()), ..Default::default() });
+        let git_manager = fake_manager(backend);
+        
+        let result = git_manager.sync_repository(&detached, SyncStrategy::FfOnly).unwrap();
+        assert!(matches!(result, SyncResult::Skipped(SkipReason::DetachedHead)));
+        let result = git_manager.sync_repository(&local, SyncStrategy::FfOnly).unwrap();
+        assert!(matches!(result, SyncResult::Skipped(SkipReason::NoUpstream { branch }) if branch == "topic"));
+    }
+    
+    #[test]
+    fn recent_commits_come_from_the_backend() {
+        let path = PathBuf::from("/ws/api");
+        let mut backend = FakeBackend::default();
+        backend.commits.insert(path.clone(), vec!["c3 third".to_string(), "b2 second".to_string(), "a1 first".to_string()]);
+        let git_manager = fake_manager(backend);
+        
+        assert_eq!(git_manager.get_recent_commits(&path, 2).unwrap(), ["c3 third", "b2 second"]);
+        assert!(git_manager.get_recent_commits(Path::new("/ws/unborn"), 2).unwrap().is_empty());
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 
 mod cli;
 mod config;
@@ -7,29 +8,63 @@ mod git;
 mod search;
 mod commands;
 mod error;
+mod output;
+mod shell;
 
 use cli::Cli;
 use commands::Commands;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Answers completion requests from the shell and exits; a no-op otherwise
+    if std::env::var_os(shell::COMPLETE_ENV).is_some() {
+        let cursor = std::env::var("_CLAP_COMPLETE_INDEX").ok().and_then(|index| index.parse().ok());
+        if let Some(name) = shell::completion_workspace(std::env::args(), cursor) {
+            config::set_workspace_override(&name);
+        }
+    }
+    CompleteEnv::with_factory(Cli::command).var(shell::COMPLETE_ENV).complete();
+    
     let cli = Cli::parse();
     
+    if let Some(name) = &cli.workspace {
+        config::set_workspace_override(name);
+    }
+    
+    output::set_format(cli.format);
+    
+    if cli.first {
+        search::set_preselection(search::Preselection::First);
+    } else if !cli.select.is_empty() {
+        search::set_preselection(search::Preselection::Positions(cli.select.clone()));
+    }
+    
     let result = match cli.command {
-        Commands::Init { path } => commands::init::execute(&path).await,
-        Commands::Add { repository, output_cd } => commands::add::execute(&repository, output_cd).await,
-        Commands::Go { pattern, output_cd } => commands::go::execute(pattern.as_deref(), output_cd).await,
-        Commands::List => commands::list::execute().await,
+        Commands::Init { path, name } => commands::init::execute(&path, &name).await,
+        Commands::Workspace { subcommand } => commands::workspace::execute(subcommand).await,
+        Commands::Add { repository, tags, output_cd } => commands::add::execute(&repository, &tags, output_cd).await,
+        Commands::Go { pattern, filter, recent, output_cd } => commands::go::execute(pattern.as_deref(), &filter, recent, output_cd).await,
+        Commands::List { filter, table, columns, template, sort, reverse } => commands::list::execute(&filter, &columns, table, template.as_deref(), sort, reverse).await,
         Commands::Remove { pattern } => commands::remove::execute(&pattern).await,
-        Commands::Sync { pattern } => commands::sync::execute(pattern.as_deref()).await,
-        Commands::Grep { pattern, repo_pattern } => commands::grep::execute(&pattern, repo_pattern.as_deref()).await,
+        Commands::Sync { pattern, filter, interactive, jobs, strategy } => commands::sync::execute(pattern.as_deref(), &filter, interactive, jobs, strategy).await,
+        Commands::Grep { pattern, repo_pattern, filter } => commands::grep::execute(&pattern, repo_pattern.as_deref(), &filter).await,
         Commands::Migrate { source } => commands::migrate::execute(&source).await,
         Commands::Config { subcommand } => commands::config::execute(subcommand).await,
-        Commands::Status => commands::status::execute().await,
+        Commands::Status { filter } => commands::status::execute(&filter).await,
+        Commands::Tag { subcommand } => commands::tag::execute(subcommand).await,
+        Commands::Alias { subcommand } => commands::alias::execute(subcommand).await,
+        Commands::Strategy { subcommand } => commands::strategy::execute(subcommand).await,
+        Commands::Reindex { prune } => commands::reindex::execute(prune).await,
+        Commands::Doctor { fix } => commands::doctor::execute(fix).await,
+        Commands::ShellInit { shell } => commands::shell_init::execute(shell).await,
     };
     
     match result {
         Ok(()) => Ok(()),
+        Err(e @ error::ProjectManError::AmbiguousMatch(_)) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(error::AMBIGUOUS_MATCH_EXIT_CODE);
+        }
         Err(e) => Err(anyhow::anyhow!("{}", e)),
     }
 }
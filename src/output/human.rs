@@ -0,0 +1,67 @@
+//! Short descriptions of repository state shared by `p list` and the search preview.
+
+use chrono::{DateTime, Utc};
+use crate::git::RepoStatus;
+
+/// `main → origin/main`, `main (no upstream)` or `detached at 1a2b3c4`.
+pub fn describe_branch(status: &RepoStatus) -> String {
+    match (&status.branch, &status.upstream) {
+        (Some(branch), Some(upstream)) => format!("{} → {}", branch, upstream),
+        (Some(branch), None) => format!("{} (no upstream)", branch),
+        (None, _) => match &status.last_commit {
+            Some(commit) => format!("detached at {}", commit.short_id),
+            None => "detached HEAD".to_string(),
+        },
+    }
+}
+
+/// Working tree changes, then how the branch compares to its upstream, e.g.
+/// `2 staged, 1 untracked, ahead 3, 1 stashed`.
+pub fn describe_status(status: &RepoStatus) -> String {
+    let mut parts = Vec::new();
+    
+    for (count, label) in [
+        (status.conflicted, "conflicted"),
+        (status.staged, "staged"),
+        (status.modified, "modified"),
+        (status.untracked, "untracked"),
+    ] {
+        if count > 0 {
+            parts.push(format!("{} {}", count, label));
+        }
+    }
+    if parts.is_empty() {
+        parts.push("clean".to_string());
+    }
+    
+    if status.ahead > 0 {
+        parts.push(format!("ahead {}", status.ahead));
+    }
+    if status.behind > 0 {
+        parts.push(format!("behind {}", status.behind));
+    }
+    if status.stashes > 0 {
+        parts.push(format!("{} stashed", status.stashes));
+    }
+    
+    parts.join(", ")
+}
+
+pub fn format_relative_time(dt: DateTime<Utc>) -> String {
+    let now = Utc::now();
+    let duration = now.signed_duration_since(dt);
+    
+    if duration.num_seconds() < 60 {
+        "just now".to_string()
+    } else if duration.num_minutes() < 60 {
+        format!("{} minutes ago", duration.num_minutes())
+    } else if duration.num_hours() < 24 {
+        format!("{} hours ago", duration.num_hours())
+    } else if duration.num_days() < 7 {
+        format!("{} days ago", duration.num_days())
+    } else if duration.num_weeks() < 4 {
+        format!("{} weeks ago", duration.num_weeks())
+    } else {
+        format!("{} months ago", duration.num_days() / 30)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,276 @@
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::Result;
+
+mod human;
+mod records;
+
+pub use human::{describe_branch, describe_status, format_relative_time};
+pub use records::{GrepHit, RepositoryRecord, SyncRecord, WorkspaceStatusRecord};
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// How `list`, `status`, `sync` and `grep` print their results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Prose for people (default)
+    #[default]
+    Human,
+    /// One JSON document: an array of records, or an object for `status`
+    Json,
+    /// One JSON record per line
+    Ndjson,
+    Yaml,
+    /// Header row plus one row per record; nested fields become `status.branch` style
+    /// columns and lists are joined with `;`
+    Csv,
+}
+
+/// Select the output format for this invocation (`--format`).
+pub fn set_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Whether results should be printed as records rather than prose.
+pub fn is_machine() -> bool {
+    format() != OutputFormat::Human
+}
+
+/// Print `records` in the selected machine-readable format.
+pub fn emit<T: Serialize>(records: &[T]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    
+    match format() {
+        // Callers print prose themselves; nothing to do
+        OutputFormat::Human => {}
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, records)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut out, record)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Yaml => write!(out, "{}", serde_yaml::to_string(records)?)?,
+        OutputFormat::Csv => write_csv(&mut out, records)?,
+    }
+    
+    out.flush()?;
+    Ok(())
+}
+
+/// Print a single record; JSON and YAML get the bare object rather than a one-element list.
+pub fn emit_one<T: Serialize>(record: &T) -> Result<()> {
+    let mut out = io::stdout().lock();
+    
+    match format() {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, record)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Yaml => write!(out, "{}", serde_yaml::to_string(record)?)?,
+        _ => {
+            drop(out);
+            return emit(std::slice::from_ref(record));
+        }
+    }
+    
+    out.flush()?;
+    Ok(())
+}
+
+/// Print human output, through `pager` (the `ui.pager` setting) when stdout is a terminal
+/// the text doesn't fit on. Falls back to printing directly if the pager can't be started.
+pub fn page(text: &str, pager: &str) -> Result<()> {
+    let mut words = pager.split_whitespace();
+    let program = match words.next() {
+        Some(program) if io::stdout().is_terminal() && !fits_on_screen(text) => program,
+        _ => return print_text(text),
+    };
+    
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    // Like git: quit if one screen is enough, keep colors, don't clear the screen on exit
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_) => return print_text(text),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        write_ignoring_closed_pipe(&mut stdin, text)?;
+    }
+    child.wait()?;
+    
+    Ok(())
+}
+
+fn print_text(text: &str) -> Result<()> {
+    write_ignoring_closed_pipe(&mut io::stdout().lock(), text)
+}
+
+/// Quitting the pager or `| head` early closes the pipe; that's not an error.
+fn write_ignoring_closed_pipe(out: &mut impl Write, text: &str) -> Result<()> {
+    match writeln!(out, "{}", text).and_then(|_| out.flush()) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn fits_on_screen(text: &str) -> bool {
+    let rows = match crossterm::terminal::size() {
+        Ok((_, rows)) if rows > 0 => rows as usize,
+        _ => 24,
+    };
+    text.lines().count() < rows
+}
+
+fn write_csv<T: Serialize>(out: &mut impl Write, records: &[T]) -> Result<()> {
+    let rows = records
+        .iter()
+        .map(|record| {
+            let mut row = Vec::new();
+            flatten("", serde_json::to_value(record)?, &mut row);
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    
+    if rows.is_empty() {
+        return Ok(());
+    }
+    
+    let mut header: Vec<String> = Vec::new();
+    for row in &rows {
+        for (column, _) in row {
+            if header.contains(column) {
+                continue;
+            }
+            // Keep a nested record's columns together, next to the placeholder a row without it left
+            let position = column.rsplit_once('.').and_then(|(parent, _)| {
+                let child_prefix = format!("{}.", parent);
+                header.iter().rposition(|other| other == parent || other.starts_with(&child_prefix))
+            });
+            match position {
+                Some(index) => header.insert(index + 1, column.clone()),
+                None => header.push(column.clone()),
+            }
+        }
+    }
+    // The placeholder of a record that is `null` in some rows gives way to the record's columns
+    let placeholders: Vec<String> = header.iter()
+        .filter(|column| header.iter().any(|other| other.starts_with(&format!("{}.", column))))
+        .cloned()
+        .collect();
+    header.retain(|column| !placeholders.contains(column));
+    
+    writeln!(out, "{}", header.iter().map(|column| csv_field(column)).collect::<Vec<_>>().join(","))?;
+    
+    for row in &rows {
+        let fields: Vec<String> = header.iter()
+            .map(|column| row.iter()
+                .find(|(name, _)| name == column)
+                .map(|(_, value)| csv_field(value))
+                .unwrap_or_default())
+            .collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    
+    Ok(())
+}
+
+/// Turn a record into `(column, value)` pairs, naming nested fields `parent.child`.
+fn flatten(prefix: &str, value: Value, row: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let column = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten(&column, value, row);
+            }
+        }
+        // A `null` nested record leaves a placeholder column; `write_csv` swaps it for the
+        // record's own columns when another row has them
+        Value::Null => row.push((prefix.to_string(), String::new())),
+        Value::String(text) => row.push((prefix.to_string(), text)),
+        Value::Array(items) => {
+            let items: Vec<String> = items
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(text) => text,
+                    other => other.to_string(),
+                })
+                .collect();
+            row.push((prefix.to_string(), items.join(";")));
+        }
+        other => row.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    fn csv(records: &[Value]) -> String {
+        let mut out = Vec::new();
+        write_csv(&mut out, records).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+    
+    #[test]
+    fn flattens_nested_fields_and_joins_lists() {
+        let mut row = Vec::new();
+        flatten("", json!({"name": "api", "status": {"ahead": 2, "dirty": true}, "tags": ["work", "rust"]}), &mut row);
+        
+        let row: Vec<(&str, &str)> = row.iter().map(|(column, value)| (column.as_str(), value.as_str())).collect();
+        assert_eq!(row, [
+            ("name", "api"),
+            ("status.ahead", "2"),
+            ("status.dirty", "true"),
+            ("tags", "work;rust"),
+        ]);
+    }
+    
+    #[test]
+    fn quotes_fields_that_need_it() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+    
+    #[test]
+    fn null_records_give_way_to_their_columns() {
+        let output = csv(&[
+            json!({"name": "a", "status": null, "path": "x/a"}),
+            json!({"name": "b", "status": {"ahead": 1, "behind": 0}, "path": "x/b"}),
+        ]);
+        
+        assert_eq!(output, "name,status.ahead,status.behind,path\na,,,x/a\nb,1,0,x/b\n");
+    }
+    
+    #[test]
+    fn writes_nothing_for_no_records() {
+        assert_eq!(csv(&[]), "");
+    }
+}
\ No newline at end of file
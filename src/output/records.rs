@@ -0,0 +1,221 @@
+//! Records printed by `--format json|ndjson|yaml|csv`.
+//!
+//! These structs are the schema scripts rely on. Keep it stable: fields may be added,
+//! but existing ones are never renamed, retyped or removed. Every field is always
+//! present, `null` when unknown, so CSV columns line up between rows. Timestamps are
+//! RFC 3339 in UTC. Enum-like values are lowercase `snake_case` strings, except sync
+//! strategies, which are spelled as on the command line (`ff-only`).
+
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::config::RepositoryConfig;
+use crate::git::{RepoStatus, SkipReason, SyncResult, SyncStrategy};
+
+/// One repository, as listed by `p list`.
+#[derive(Debug, Serialize)]
+pub struct RepositoryRecord {
+    pub name: String,
+    /// Absolute path of the working tree
+    pub path: PathBuf,
+    pub url: String,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    pub added_at: DateTime<Utc>,
+    pub last_sync: Option<DateTime<Utc>>,
+    /// Per-repository `p sync` strategy, `null` for the default
+    pub sync_strategy: Option<SyncStrategy>,
+    /// Whether the working tree exists on disk
+    pub exists: bool,
+    /// `null` when the directory is missing or git couldn't read it
+    pub status: Option<StatusRecord>,
+}
+
+impl RepositoryRecord {
+    pub fn new(name: &str, repo_config: &RepositoryConfig, path: PathBuf, status: Option<&RepoStatus>) -> Self {
+        Self {
+            name: name.to_string(),
+            exists: path.exists(),
+            path,
+            url: repo_config.url.clone(),
+            tags: repo_config.tags.clone(),
+            aliases: repo_config.aliases.clone(),
+            added_at: repo_config.added_at,
+            last_sync: repo_config.last_sync,
+            sync_strategy: repo_config.sync_strategy,
+            status: status.map(StatusRecord::from),
+        }
+    }
+}
+
+/// Git state of a working tree.
+#[derive(Debug, Serialize)]
+pub struct StatusRecord {
+    /// `null` when HEAD is detached
+    pub branch: Option<String>,
+    /// e.g. `origin/main`, `null` without one
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub staged: usize,
+    pub modified: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    pub stashes: usize,
+    /// No staged, modified, untracked or conflicted files
+    pub clean: bool,
+    /// `null` on a branch without commits
+    pub last_commit: Option<CommitRecord>,
+}
+
+impl From<&RepoStatus> for StatusRecord {
+    fn from(status: &RepoStatus) -> Self {
+        Self {
+            branch: status.branch.clone(),
+            upstream: status.upstream.clone(),
+            ahead: status.ahead,
+            behind: status.behind,
+            staged: status.staged,
+            modified: status.modified,
+            untracked: status.untracked,
+            conflicted: status.conflicted,
+            stashes: status.stashes,
+            clean: status.is_clean(),
+            last_commit: status.last_commit.as_ref().map(|commit| CommitRecord {
+                id: commit.short_id.clone(),
+                date: commit.date,
+                subject: commit.subject.clone(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommitRecord {
+    /// Abbreviated hash
+    pub id: String,
+    pub date: DateTime<Utc>,
+    pub subject: String,
+}
+
+/// Workspace summary printed by `p status`; counts are numbers of repositories.
+#[derive(Debug, Default, Serialize)]
+pub struct WorkspaceStatusRecord {
+    pub workspace: String,
+    pub path: PathBuf,
+    pub exists: bool,
+    pub total: usize,
+    pub clean: usize,
+    pub dirty: usize,
+    pub ahead: usize,
+    pub behind: usize,
+    pub conflicted: usize,
+    pub stashed: usize,
+    pub detached: usize,
+    pub no_upstream: usize,
+    pub missing: usize,
+    pub errors: usize,
+}
+
+/// Outcome of syncing one repository with `p sync`.
+#[derive(Debug, Serialize)]
+pub struct SyncRecord {
+    pub name: String,
+    pub strategy: SyncStrategy,
+    /// `up_to_date`, `fetched`, `updated`, `rebased`, `diverged`, `rebase_conflict`,
+    /// `autostash_conflict`, `skipped`, `missing` (no working tree) or `error`
+    pub result: &'static str,
+    /// Upstream commits brought in (for `fetched`: available but not merged)
+    pub commits_pulled: usize,
+    /// Local commits on top of the upstream (`diverged`, `rebase_conflict`, `rebased`)
+    pub ahead: usize,
+    /// Upstream commits missing locally (`diverged`, `rebase_conflict`)
+    pub behind: usize,
+    /// `<short hash> <subject>` of the newest upstream commits brought in
+    pub new_commits: Vec<String>,
+    /// `detached_head`, `no_upstream` or `local_changes` when `result` is `skipped`
+    pub skip_reason: Option<&'static str>,
+    /// Error message when `result` is `error`
+    pub error: Option<String>,
+}
+
+impl SyncRecord {
+    pub fn new(name: &str, strategy: SyncStrategy, result: &crate::error::Result<SyncResult>) -> Self {
+        let mut record = Self {
+            name: name.to_string(),
+            strategy,
+            result: "error",
+            commits_pulled: 0,
+            ahead: 0,
+            behind: 0,
+            new_commits: Vec::new(),
+            skip_reason: None,
+            error: None,
+        };
+        
+        match result {
+            Ok(SyncResult::UpToDate) => record.result = "up_to_date",
+            Ok(SyncResult::Fetched { behind, new_commits }) => {
+                record.result = "fetched";
+                record.commits_pulled = *behind;
+                record.behind = *behind;
+                record.new_commits = new_commits.clone();
+            }
+            Ok(SyncResult::Updated { commits_pulled, new_commits }) => {
+                record.result = "updated";
+                record.commits_pulled = *commits_pulled;
+                record.new_commits = new_commits.clone();
+            }
+            Ok(SyncResult::Rebased { commits_pulled, local_commits, new_commits }) => {
+                record.result = "rebased";
+                record.commits_pulled = *commits_pulled;
+                record.ahead = *local_commits;
+                record.new_commits = new_commits.clone();
+            }
+            Ok(SyncResult::Conflict { ahead, behind }) => {
+                record.result = "diverged";
+                record.ahead = *ahead;
+                record.behind = *behind;
+            }
+            Ok(SyncResult::RebaseConflict { ahead, behind }) => {
+                record.result = "rebase_conflict";
+                record.ahead = *ahead;
+                record.behind = *behind;
+            }
+            Ok(SyncResult::AutostashConflict { commits_pulled, new_commits }) => {
+                record.result = "autostash_conflict";
+                record.commits_pulled = *commits_pulled;
+                record.new_commits = new_commits.clone();
+            }
+            Ok(SyncResult::Skipped(reason)) => {
+                record.result = "skipped";
+                record.skip_reason = Some(match reason {
+                    SkipReason::DetachedHead => "detached_head",
+                    SkipReason::NoUpstream { .. } => "no_upstream",
+                    SkipReason::LocalChanges => "local_changes",
+                });
+            }
+            Err(e) => record.error = Some(e.to_string()),
+        }
+        
+        record
+    }
+    
+    /// Record for a repository whose working tree doesn't exist.
+    pub fn missing(name: &str, strategy: SyncStrategy) -> Self {
+        let mut record = Self::new(name, strategy, &Ok(SyncResult::UpToDate));
+        record.result = "missing";
+        record
+    }
+}
+
+/// One matching line found by `p grep`.
+#[derive(Debug, Serialize)]
+pub struct GrepHit {
+    pub repository: String,
+    /// File path relative to the repository root
+    pub file: String,
+    /// 1-based line number
+    pub line: usize,
+    pub text: String,
+}
\ No newline at end of file
@@ -1,51 +1,256 @@
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
-use std::io::{self, Write};
-use crossterm::{
-    cursor,
-    event::{self, Event, KeyCode, KeyEvent},
-    execute,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-    terminal::{self, ClearType},
-};
-use crate::config::RepositoryConfig;
-use crate::error::Result;
+use std::path::Path;
+use std::sync::OnceLock;
+use crate::config::{GlobalConfig, RepositoryConfig, SearchConfig, VisitHistory, WorkspaceRegistry};
+use crate::git::{GitManager, RepoStatus};
+use crate::error::{ProjectManError, Result};
 
+pub mod query;
+mod preview;
+mod selector;
+
+pub use query::Query;
+use selector::{Selector, open_terminal};
+
+static PRESELECTION: OnceLock<Preselection> = OnceLock::new();
+
+/// How to settle several matches without asking (`--first` / `--select`).
+#[derive(Debug, Clone)]
+pub enum Preselection {
+    First,
+    // 1-based positions in the ranked candidate list
+    Positions(Vec<usize>),
+}
+
+impl Preselection {
+    fn pick(&self, candidates: &[SearchResult]) -> Result<Vec<SearchResult>> {
+        let positions = match self {
+            Preselection::First => return Ok(candidates.iter().take(1).cloned().collect()),
+            Preselection::Positions(positions) => positions,
+        };
+        
+        positions
+            .iter()
+            .map(|&position| {
+                position.checked_sub(1)
+                    .and_then(|index| candidates.get(index))
+                    .cloned()
+                    .ok_or_else(|| ProjectManError::InvalidQuery(format!(
+                        "--select {} is out of range; {} repositories match",
+                        position,
+                        candidates.len()
+                    )))
+            })
+            .collect()
+    }
+}
+
+/// Resolve ambiguous matches for this invocation instead of prompting.
+pub fn set_preselection(preselection: Preselection) {
+    let _ = PRESELECTION.set(preselection);
+}
+
+/// The error for several matches when there is no terminal to choose on.
+fn ambiguous(candidates: &[SearchResult]) -> ProjectManError {
+    let listing = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| format!("  {}. {} ({})", i + 1, candidate.name, candidate.repo_config.path))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ProjectManError::AmbiguousMatch(listing)
+}
+
+/// Repository attribute a search pattern matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchField {
+    Alias,
+    Path,
+    Name,
+    Tag,
+    Url,
+}
+
+impl MatchField {
+    // Explicit aliases win over structural fields; URLs repeat the path with noise around it
+    fn weight(self) -> f64 {
+        match self {
+            MatchField::Alias => 1.2,
+            MatchField::Path => 1.0,
+            MatchField::Name => 0.9,
+            MatchField::Tag => 0.9,
+            MatchField::Url => 0.7,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchField::Alias => "alias",
+            MatchField::Path => "path",
+            MatchField::Name => "name",
+            MatchField::Tag => "tag",
+            MatchField::Url => "url",
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct SearchResult {
     pub name: String,
     pub repo_config: RepositoryConfig,
     pub score: i64,
+    pub matched_field: MatchField,
+    pub matched_text: String,
+    // Character positions of the match within `matched_text`
+    pub match_indices: Vec<usize>,
+}
+
+impl SearchResult {
+    /// A candidate offered without a pattern, e.g. every repository carrying a tag.
+    pub fn unmatched(name: String, repo_config: RepositoryConfig) -> Self {
+        let matched_text = name.clone();
+        Self {
+            name,
+            repo_config,
+            score: 0,
+            matched_field: MatchField::Name,
+            matched_text,
+            match_indices: Vec::new(),
+        }
+    }
 }
 
+// Skim points added per unit of log-frecency; a repository visited ~20 times today
+// outranks one whose name merely matches a little better
+const FRECENCY_WEIGHT: f64 = 20.0;
+
 pub struct FuzzySearch {
     matcher: SkimMatcherV2,
+    threshold: f64,
+    max_results: usize,
+    history: VisitHistory,
 }
 
 impl FuzzySearch {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self> {
+        let config = GlobalConfig::load()?;
+        let mut search = Self::with_config(&config.search);
+        // Ranking works without history, so an unreadable file shouldn't block navigation
+        search.history = VisitHistory::load().unwrap_or_default();
+        Ok(search)
+    }
+    
+    pub fn with_config(config: &SearchConfig) -> Self {
+        let matcher = if config.case_sensitive {
+            SkimMatcherV2::default().respect_case()
+        } else {
+            SkimMatcherV2::default().ignore_case()
+        };
+        
         Self {
-            matcher: SkimMatcherV2::default(),
+            matcher,
+            threshold: config.fuzzy_threshold,
+            max_results: config.max_results,
+            history: VisitHistory::default(),
         }
     }
     
     pub fn search(&self, repositories: &[(String, RepositoryConfig)], pattern: &str) -> Vec<SearchResult> {
+        // Skim scores are unbounded, so rate each match against the pattern matching itself
+        let best_score = self.matcher.fuzzy_match(pattern, pattern).unwrap_or(0).max(1);
+        
         let mut results: Vec<SearchResult> = repositories
             .iter()
             .filter_map(|(name, repo_config)| {
-                let score = self.matcher.fuzzy_match(name, pattern)?;
+                let (field, text, score, indices) = self.best_field_match(name, repo_config, pattern, best_score)?;
+                // Weights only rank; `best_field_match` applied the threshold to the raw score
+                let score = score as f64 * field.weight();
+                let boost = FRECENCY_WEIGHT * self.history.frecency(name).ln_1p();
                 Some(SearchResult {
                     name: name.clone(),
                     repo_config: repo_config.clone(),
-                    score,
+                    score: (score + boost).round() as i64,
+                    matched_field: field,
+                    matched_text: text.to_string(),
+                    match_indices: indices,
                 })
             })
             .collect();
         
         // Sort by score (descending)
-        results.sort_by(|a, b| b.score.cmp(&a.score));
+        results.sort_by_key(|r| std::cmp::Reverse(r.score));
+        results.truncate(self.max_results);
         
         results
     }
     
+    /// Select repositories for a structured query.
+    ///
+    /// Qualifiers filter first; remaining fuzzy text ranks what is left. A query with
+    /// no fuzzy text returns every repository passing the qualifiers, most frequently
+    /// and recently visited first, and not capped by `max_results`. `status_of` is only
+    /// called for queries that filter on live git state.
+    pub fn search_query(
+        &self,
+        repositories: &[(String, RepositoryConfig)],
+        query: &Query,
+        status_of: impl Fn(&RepositoryConfig) -> Option<RepoStatus>,
+    ) -> Vec<SearchResult> {
+        let needs_status = query.needs_status();
+        let candidates: Vec<(String, RepositoryConfig)> = repositories
+            .iter()
+            .filter(|(_, repo_config)| {
+                let status = if needs_status { status_of(repo_config) } else { None };
+                query.matches(repo_config, status.as_ref())
+            })
+            .cloned()
+            .collect();
+        
+        if !query.text.is_empty() {
+            return self.search(&candidates, &query.text);
+        }
+        
+        let mut results: Vec<SearchResult> = candidates
+            .into_iter()
+            .map(|(name, repo_config)| SearchResult::unmatched(name, repo_config))
+            .collect();
+        results.sort_by(|a, b| {
+            self.history.frecency(&b.name)
+                .total_cmp(&self.history.frecency(&a.name))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        results
+    }
+    
+    /// The field matching `pattern` best, with its raw skim score.
+    ///
+    /// Only fields whose own match reaches `threshold` of `best_score` take part, so
+    /// the threshold means the same whichever field matched.
+    fn best_field_match<'a>(
+        &self,
+        name: &'a str,
+        repo_config: &'a RepositoryConfig,
+        pattern: &str,
+        best_score: i64,
+    ) -> Option<(MatchField, &'a str, i64, Vec<usize>)> {
+        let fields = std::iter::once((MatchField::Path, repo_config.path.as_str()))
+            .chain(std::iter::once((MatchField::Name, name)))
+            .chain(std::iter::once((MatchField::Url, repo_config.url.as_str())))
+            .chain(repo_config.tags.iter().map(|tag| (MatchField::Tag, tag.as_str())))
+            .chain(repo_config.aliases.iter().map(|alias| (MatchField::Alias, alias.as_str())));
+        
+        fields
+            .filter_map(|(field, text)| {
+                let (score, indices) = self.matcher.fuzzy_indices(text, pattern)?;
+                if (score as f64) / (best_score as f64) < self.threshold {
+                    return None;
+                }
+                Some((field, text, score, indices))
+            })
+            .max_by(|a, b| (a.2 as f64 * a.0.weight()).total_cmp(&(b.2 as f64 * b.0.weight())))
+    }
+    
+    /// Let the user pick one of `candidates`, typing to narrow them down further.
     pub fn interactive_select(&self, candidates: Vec<SearchResult>) -> Result<Option<SearchResult>> {
         if candidates.is_empty() {
             return Ok(None);
@@ -55,95 +260,198 @@ impl FuzzySearch {
             return Ok(Some(candidates.into_iter().next().unwrap()));
         }
         
-        // Enable raw mode for interactive selection
-        terminal::enable_raw_mode()?;
-        
-        let mut selected = 0;
-        let result = loop {
-            // Clear screen and move cursor to top
-            execute!(
-                io::stdout(),
-                terminal::Clear(ClearType::All),
-                cursor::MoveTo(0, 0)
-            )?;
-            
-            // Display header
-            execute!(
-                io::stdout(),
-                SetForegroundColor(Color::Yellow),
-                Print("📋 Select a repository (use ↑/↓ to navigate, Enter to select, Esc to cancel):\n\n"),
-                ResetColor
-            )?;
-            
-            // Display candidates
-            for (i, candidate) in candidates.iter().enumerate() {
-                let prefix = if i == selected { "➤ " } else { "  " };
-                let color = if i == selected { Color::Green } else { Color::White };
-                
-                execute!(
-                    io::stdout(),
-                    SetForegroundColor(color),
-                    Print(format!("{}{}\n", prefix, candidate.name)),
-                    ResetColor
-                )?;
-                
-                // Show path for selected item
-                if i == selected {
-                    execute!(
-                        io::stdout(),
-                        SetForegroundColor(Color::DarkGrey),
-                        Print(format!("    📁 {}\n", candidate.repo_config.path)),
-                        ResetColor
-                    )?;
-                }
+        let selected = match PRESELECTION.get() {
+            Some(Preselection::Positions(positions)) if positions.len() > 1 => {
+                return Err(ProjectManError::InvalidQuery("--select takes a single number here".to_string()));
             }
-            
-            io::stdout().flush()?;
-            
-            // Handle input
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match code {
-                    KeyCode::Up => {
-                        if selected > 0 {
-                            selected -= 1;
-                        }
-                    }
-                    KeyCode::Down => {
-                        if selected < candidates.len() - 1 {
-                            selected += 1;
-                        }
-                    }
-                    KeyCode::Enter => {
-                        break Some(candidates[selected].clone());
-                    }
-                    KeyCode::Esc => {
-                        break None;
-                    }
-                    _ => {}
-                }
+            Some(preselection) => preselection.pick(&candidates)?,
+            None => {
+                let terminal = open_terminal().ok_or_else(|| ambiguous(&candidates))?;
+                Selector::new(self, candidates).run(terminal)?
             }
         };
         
-        // Disable raw mode
-        terminal::disable_raw_mode()?;
+        Ok(selected.into_iter().next())
+    }
+    
+    /// Let the user pick any number of `candidates`; an empty result means they cancelled.
+    pub fn interactive_select_many(&self, candidates: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        if candidates.len() <= 1 {
+            return Ok(candidates);
+        }
         
-        // Clear screen
-        execute!(
-            io::stdout(),
-            terminal::Clear(ClearType::All),
-            cursor::MoveTo(0, 0)
-        )?;
+        if let Some(preselection) = PRESELECTION.get() {
+            return preselection.pick(&candidates);
+        }
         
-        Ok(result)
+        let terminal = open_terminal().ok_or_else(|| ambiguous(&candidates))?;
+        Selector::new(self, candidates).multi().run(terminal)
     }
 }
 
-impl Clone for SearchResult {
-    fn clone(&self) -> Self {
-        Self {
-            name: self.name.clone(),
-            repo_config: self.repo_config.clone(),
-            score: self.score,
+/// Live status lookup for `FuzzySearch::search_query`.
+pub fn git_status_of<'a>(
+    git_manager: &'a GitManager,
+    workspace_path: &'a Path,
+) -> impl Fn(&RepositoryConfig) -> Option<RepoStatus> + 'a {
+    move |repo_config| git_manager.get_repository_status(&workspace_path.join(&repo_config.path)).ok()
+}
+
+/// Resolve `pattern` to a single repository, asking the user when several match.
+pub fn select_repository(pattern: &str) -> Result<Option<SearchResult>> {
+    let global_config = GlobalConfig::load()?;
+    let workspace_registry = WorkspaceRegistry::load_from_workspace()?;
+    let git_manager = GitManager::new()?;
+    let fuzzy_search = FuzzySearch::new()?;
+    
+    let owned_repos: Vec<(String, _)> = workspace_registry.list_repositories()
+        .into_iter()
+        .map(|(name, config)| (name.clone(), config.clone()))
+        .collect();
+    
+    let query = Query::parse(pattern)?;
+    let status_of = git_status_of(&git_manager, global_config.get_workspace_path());
+    let results = fuzzy_search.search_query(&owned_repos, &query, status_of);
+    
+    if results.is_empty() {
+        println!("❌ No repositories found matching '{}'", pattern);
+        return Ok(None);
+    }
+    
+    let selected = fuzzy_search.interactive_select(results)?;
+    if selected.is_none() {
+        println!("❌ No repository selected.");
+    }
+    
+    Ok(selected)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::history::VisitStats;
+    use crate::git::tests::{FakeBackend, fake_manager};
+    
+    fn repositories(paths: &[&str]) -> Vec<(String, RepositoryConfig)> {
+        paths
+            .iter()
+            .map(|path| {
+                let name = path.replace('/', "_");
+                (name, RepositoryConfig::new(path.to_string(), String::new(), Vec::new()))
+            })
+            .collect()
+    }
+    
+    fn names(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|result| result.name.as_str()).collect()
+    }
+    
+    #[test]
+    fn threshold_drops_weak_matches() {
+        let repos = repositories(&["h/x/api", "h/x/canopy-trail"]);
+        let lenient = FuzzySearch::with_config(&SearchConfig { fuzzy_threshold: 0.0, ..SearchConfig::default() });
+        let strict = FuzzySearch::with_config(&SearchConfig::default());
+        
+        assert_eq!(lenient.search(&repos, "api").len(), 2);
+        assert_eq!(names(&strict.search(&repos, "api")), ["h_x_api"]);
+    }
+    
+    #[test]
+    fn threshold_ignores_field_weights() {
+        let search = FuzzySearch::with_config(&SearchConfig::default());
+        
+        let mut aliased = RepositoryConfig::new("h/x/one".to_string(), String::new(), Vec::new());
+        aliased.aliases = vec!["cleanup-tidy".to_string()];
+        assert!(search.search(&[("one".to_string(), aliased)], "api").is_empty());
+        
+        let by_url = RepositoryConfig::new("h/x/one".to_string(), "warehouse-pixi".to_string(), Vec::new());
+        let results = search.search(&[("one".to_string(), by_url)], "api");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matched_field, MatchField::Url);
+    }
+    
+    #[test]
+    fn max_results_caps_the_ranking() {
+        let repos = repositories(&["h/a/api-1", "h/a/api-2", "h/a/api-3"]);
+        let search = FuzzySearch::with_config(&SearchConfig { max_results: 2, ..SearchConfig::default() });
+        
+        assert_eq!(search.search(&repos, "api").len(), 2);
+    }
+    
+    #[test]
+    fn case_sensitive_search_respects_case() {
+        let repos = repositories(&["h/x/Api"]);
+        let sensitive = FuzzySearch::with_config(&SearchConfig { case_sensitive: true, ..SearchConfig::default() });
+        let insensitive = FuzzySearch::with_config(&SearchConfig::default());
+        
+        assert!(sensitive.search(&repos, "api").is_empty());
+        assert_eq!(insensitive.search(&repos, "api").len(), 1);
+    }
+    
+    fn visited(visits: &[(&str, u32)]) -> VisitHistory {
+        let visits = visits
+            .iter()
+            .map(|&(name, count)| (name.to_string(), VisitStats { count, last_visit: chrono::Utc::now() }))
+            .collect();
+        VisitHistory { visits }
+    }
+    
+    #[test]
+    fn frecency_lifts_frequently_visited_repositories() {
+        let repos = repositories(&["h/a/api-server", "h/b/api-server"]);
+        let mut search = FuzzySearch::with_config(&SearchConfig::default());
+        search.history = visited(&[("h_b_api-server", 10)]);
+        
+        assert_eq!(names(&search.search(&repos, "api")), ["h_b_api-server", "h_a_api-server"]);
+    }
+    
+    #[test]
+    fn query_without_text_orders_by_frecency_then_name() {
+        let repos = repositories(&["h/a/one", "h/a/two", "h/a/three"]);
+        let mut search = FuzzySearch::with_config(&SearchConfig::default());
+        search.history = visited(&[("h_a_two", 1), ("h_a_three", 5)]);
+        
+        let results = search.search_query(&repos, &Query::parse("").unwrap(), |_| None);
+        assert_eq!(names(&results), ["h_a_three", "h_a_two", "h_a_one"]);
+    }
+    
+    fn candidates(count: usize) -> Vec<SearchResult> {
+        repositories(&["h/a/one", "h/a/two", "h/a/three"][..count])
+            .into_iter()
+            .map(|(name, repo_config)| SearchResult::unmatched(name, repo_config))
+            .collect()
+    }
+    
+    #[test]
+    fn preselection_picks_the_first_candidate() {
+        assert_eq!(names(&Preselection::First.pick(&candidates(3)).unwrap()), ["h_a_one"]);
+        assert!(Preselection::First.pick(&[]).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn preselection_picks_one_based_positions_in_order() {
+        let picked = Preselection::Positions(vec![3, 1]).pick(&candidates(3)).unwrap();
+        assert_eq!(names(&picked), ["h_a_three", "h_a_one"]);
+    }
+    
+    #[test]
+    fn preselection_rejects_positions_out_of_range() {
+        for position in [0, 3] {
+            let picked = Preselection::Positions(vec![position]).pick(&candidates(2));
+            assert!(matches!(picked, Err(ProjectManError::InvalidQuery(_))), "{}", position);
         }
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn status_qualifiers_ask_the_git_backend() {
+        let workspace = Path::new("/ws");
+        let repos = repositories(&["h/a/clean", "h/a/dirty"]);
+        let mut backend = FakeBackend::default();
+        backend.statuses.insert(workspace.join("h/a/clean"), RepoStatus::default());
+        backend.statuses.insert(workspace.join("h/a/dirty"), RepoStatus { untracked: 1, ..Default::default() });
+        let git_manager = fake_manager(backend);
+        let search = FuzzySearch::with_config(&SearchConfig::default());
+        
+        let results = search.search_query(&repos, &Query::parse("dirty:true").unwrap(), git_status_of(&git_manager, workspace));
+        assert_eq!(names(&results), ["h_a_dirty"]);
+    }
+}
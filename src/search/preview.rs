@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use crate::output::{describe_branch, describe_status, format_relative_time};
+use crate::config::RepositoryConfig;
+use crate::git::{GitManager, RepoStatus};
+
+const RECENT_COMMITS: usize = 5;
+const README_LINES: usize = 20;
+
+/// Details shown next to the highlighted repository in the selector.
+pub struct RepoPreview {
+    exists: bool,
+    status: Option<RepoStatus>,
+    commits: Vec<String>,
+    readme: Vec<String>,
+}
+
+impl RepoPreview {
+    /// Gather the preview, treating anything git can't tell us as unknown rather than an error.
+    pub fn load(repo_path: &Path) -> Self {
+        if !repo_path.exists() {
+            return Self { exists: false, status: None, commits: Vec::new(), readme: Vec::new() };
+        }
+        
+        let (status, commits) = match GitManager::new().ok() {
+            Some(git_manager) => (
+                git_manager.get_repository_status(repo_path).ok(),
+                git_manager.get_recent_commits(repo_path, RECENT_COMMITS).unwrap_or_default(),
+            ),
+            None => (None, Vec::new()),
+        };
+        
+        Self { exists: true, status, commits, readme: read_readme(repo_path) }
+    }
+    
+    pub fn lines(&self, repo_config: &RepositoryConfig) -> Vec<String> {
+        let mut lines = Vec::new();
+        
+        if !self.exists {
+            lines.push("❌ Directory not found".to_string());
+            lines.push("💡 Use 'p doctor --fix' to re-clone or forget it".to_string());
+            return lines;
+        }
+        
+        match &self.status {
+            Some(status) => {
+                lines.push(format!("🌿 {}", describe_branch(status)));
+                lines.push(format!("📊 {}", describe_status(status)));
+            }
+            None => lines.push("📊 unknown".to_string()),
+        }
+        
+        if !repo_config.tags.is_empty() {
+            lines.push(format!("🏷️  {}", repo_config.tags.join(", ")));
+        }
+        
+        let last_sync = repo_config.last_sync
+            .map(format_relative_time)
+            .unwrap_or_else(|| "never".to_string());
+        lines.push(format!("🔄 Last sync: {}", last_sync));
+        
+        if !self.commits.is_empty() {
+            lines.push(String::new());
+            lines.push("📝 Recent commits".to_string());
+            lines.extend(self.commits.iter().map(|commit| format!("   {}", commit)));
+        }
+        
+        if !self.readme.is_empty() {
+            lines.push(String::new());
+            lines.push("📖 README".to_string());
+            lines.extend(self.readme.iter().map(|line| format!("   {}", line)));
+        }
+        
+        lines
+    }
+}
+
+fn read_readme(repo_path: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(repo_path) else {
+        return Vec::new();
+    };
+    
+    let mut candidates: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.file_name()
+            .is_some_and(|name| name.to_string_lossy().to_uppercase().starts_with("README")))
+        .collect();
+    candidates.sort();
+    
+    candidates.first()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|content| content.lines().take(README_LINES).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Loads previews on a background thread so a slow repository never blocks navigation.
+///
+/// A single worker handles requests one at a time and skips those the cursor has already
+/// moved past, so holding an arrow key doesn't pile up git processes.
+pub struct PreviewLoader {
+    cache: HashMap<String, RepoPreview>,
+    /// The most recent request, until its preview arrives
+    pending: Option<String>,
+    requests: Sender<(String, PathBuf)>,
+    results: Receiver<(String, RepoPreview)>,
+}
+
+impl PreviewLoader {
+    pub fn new() -> Self {
+        let (requests, request_receiver) = mpsc::channel::<(String, PathBuf)>();
+        let (result_sender, results) = mpsc::channel();
+        
+        // Exits once the loader, and with it the request sender, is dropped
+        thread::spawn(move || {
+            while let Ok(mut request) = request_receiver.recv() {
+                while let Ok(newer) = request_receiver.try_recv() {
+                    request = newer;
+                }
+                
+                let (name, repo_path) = request;
+                let preview = RepoPreview::load(&repo_path);
+                if result_sender.send((name, preview)).is_err() {
+                    break;
+                }
+            }
+        });
+        
+        Self { cache: HashMap::new(), pending: None, requests, results }
+    }
+    
+    pub fn get(&self, name: &str) -> Option<&RepoPreview> {
+        self.cache.get(name)
+    }
+    
+    /// Load `name` next, unless it is already loaded or was the last one asked for.
+    pub fn request(&mut self, name: &str, repo_path: PathBuf) {
+        if self.cache.contains_key(name) || self.pending.as_deref() == Some(name) {
+            return;
+        }
+        
+        if self.requests.send((name.to_string(), repo_path)).is_ok() {
+            self.pending = Some(name.to_string());
+        }
+    }
+    
+    /// Collect finished previews; returns whether any arrived.
+    pub fn poll(&mut self) -> bool {
+        let mut arrived = false;
+        
+        while let Ok((name, preview)) = self.results.try_recv() {
+            if self.pending.as_deref() == Some(name.as_str()) {
+                self.pending = None;
+            }
+            self.cache.insert(name, preview);
+            arrived = true;
+        }
+        
+        arrived
+    }
+}
\ No newline at end of file
@@ -0,0 +1,279 @@
+use chrono::{Duration, Utc};
+use crate::config::RepositoryConfig;
+use crate::error::{ProjectManError, Result};
+use crate::git::RepoStatus;
+
+/// A repository selection such as `owner:my-org dirty:true !tag:archived api`.
+///
+/// Words of the form `key:value` for a known key are qualifiers; everything else is
+/// fuzzy text. A leading `!` (or `-`) negates a qualifier, or excludes repositories
+/// whose path contains a plain word.
+///
+/// | Qualifier          | Matches                                            |
+/// |--------------------|----------------------------------------------------|
+/// | `host:<host>`      | first path component, e.g. `gitlab.com`            |
+/// | `owner:<owner>`    | second path component                              |
+/// | `tag:<tag>`        | repositories carrying the tag                      |
+/// | `dirty:<bool>`     | uncommitted changes                                |
+/// | `ahead:<bool>`     | local commits not pushed                           |
+/// | `behind:<bool>`    | upstream commits not pulled                        |
+/// | `synced:>7d`       | last sync older than 7 days (`<` for newer)        |
+/// | `synced:never`     | never synced                                       |
+///
+/// Durations take `m`, `h`, `d` or `w` suffixes.
+#[derive(Debug, Default)]
+pub struct Query {
+    pub text: String,
+    filters: Vec<Filter>,
+}
+
+#[derive(Debug)]
+struct Filter {
+    negated: bool,
+    kind: FilterKind,
+}
+
+#[derive(Debug)]
+enum FilterKind {
+    Host(String),
+    Owner(String),
+    Tag(String),
+    Dirty(bool),
+    Ahead(bool),
+    Behind(bool),
+    SyncedBefore(Duration),
+    SyncedWithin(Duration),
+    NeverSynced,
+    PathContains(String),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut query = Query::default();
+        let mut words = Vec::new();
+        
+        for token in input.split_whitespace() {
+            let (negated, body) = match token.strip_prefix('!').or_else(|| token.strip_prefix('-')) {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, token),
+            };
+            
+            match parse_qualifier(body)? {
+                Some(kind) => query.filters.push(Filter { negated, kind }),
+                None if negated => query.filters.push(Filter {
+                    negated,
+                    kind: FilterKind::PathContains(body.to_lowercase()),
+                }),
+                None => words.push(token),
+            }
+        }
+        
+        // Skim has no notion of separate terms; concatenated words still match in order
+        query.text = words.concat();
+        Ok(query)
+    }
+    
+    /// Whether evaluating the query needs the repository's live git status.
+    pub fn needs_status(&self) -> bool {
+        self.filters.iter().any(|filter| matches!(
+            filter.kind,
+            FilterKind::Dirty(_) | FilterKind::Ahead(_) | FilterKind::Behind(_)
+        ))
+    }
+    
+    /// Check the qualifiers; `status` is only consulted when `needs_status` is true.
+    ///
+    /// Status qualifiers never match a repository whose status is unknown, negated or not.
+    pub fn matches(&self, repo_config: &RepositoryConfig, status: Option<&RepoStatus>) -> bool {
+        self.filters.iter().all(|filter| {
+            match filter.kind.evaluate(repo_config, status) {
+                Some(result) => result != filter.negated,
+                None => false,
+            }
+        })
+    }
+}
+
+impl FilterKind {
+    fn evaluate(&self, repo_config: &RepositoryConfig, status: Option<&RepoStatus>) -> Option<bool> {
+        let mut components = repo_config.path.split('/');
+        
+        let result = match self {
+            FilterKind::Host(host) => components.next().is_some_and(|c| c.eq_ignore_ascii_case(host)),
+            FilterKind::Owner(owner) => components.nth(1).is_some_and(|c| c.eq_ignore_ascii_case(owner)),
+            FilterKind::Tag(tag) => repo_config.has_tag(tag),
+            FilterKind::Dirty(dirty) => status?.is_clean() != *dirty,
+            FilterKind::Ahead(ahead) => (status?.ahead > 0) == *ahead,
+            FilterKind::Behind(behind) => (status?.behind > 0) == *behind,
+            FilterKind::SyncedBefore(age) => repo_config.last_sync
+                .is_none_or(|last_sync| Utc::now() - last_sync > *age),
+            FilterKind::SyncedWithin(age) => repo_config.last_sync
+                .is_some_and(|last_sync| Utc::now() - last_sync <= *age),
+            FilterKind::NeverSynced => repo_config.last_sync.is_none(),
+            FilterKind::PathContains(text) => repo_config.path.to_lowercase().contains(text),
+        };
+        
+        Some(result)
+    }
+}
+
+fn parse_qualifier(token: &str) -> Result<Option<FilterKind>> {
+    let Some((key, value)) = token.split_once(':') else {
+        return Ok(None);
+    };
+    
+    let kind = match key {
+        "host" => FilterKind::Host(value.to_string()),
+        "owner" => FilterKind::Owner(value.to_string()),
+        "tag" => FilterKind::Tag(value.to_string()),
+        "dirty" => FilterKind::Dirty(parse_bool(key, value)?),
+        "ahead" => FilterKind::Ahead(parse_bool(key, value)?),
+        "behind" => FilterKind::Behind(parse_bool(key, value)?),
+        "synced" => parse_synced(value)?,
+        // Not a qualifier, e.g. part of an SSH URL
+        _ => return Ok(None),
+    };
+    
+    if value.is_empty() {
+        return Err(ProjectManError::InvalidQuery(format!("'{}:' needs a value", key)));
+    }
+    
+    Ok(Some(kind))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => Err(ProjectManError::InvalidQuery(format!("'{}:' expects true or false, got '{}'", key, value))),
+    }
+}
+
+fn parse_synced(value: &str) -> Result<FilterKind> {
+    if value == "never" {
+        return Ok(FilterKind::NeverSynced);
+    }
+    
+    if let Some(age) = value.strip_prefix('>') {
+        Ok(FilterKind::SyncedBefore(parse_duration(age)?))
+    } else {
+        let age = value.strip_prefix('<').unwrap_or(value);
+        Ok(FilterKind::SyncedWithin(parse_duration(age)?))
+    }
+}
+
+fn parse_duration(value: &str) -> Result<Duration> {
+    let invalid = || ProjectManError::InvalidQuery(format!(
+        "Invalid duration '{}', expected a number followed by m, h, d or w (e.g. 7d)",
+        value
+    ));
+    
+    let unit_start = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    
+    let duration = match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+    
+    duration.ok_or_else(|| ProjectManError::InvalidQuery(format!("Duration '{}' is too large", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    
+    fn repo(path: &str, tags: &[&str], last_sync: Option<DateTime<Utc>>) -> RepositoryConfig {
+        let tags = tags.iter().map(|tag| tag.to_string()).collect();
+        let mut config = RepositoryConfig::new(path.to_string(), format!("https://{}.git", path), tags);
+        config.last_sync = last_sync;
+        config
+    }
+    
+    #[test]
+    fn parses_duration_units() {
+        assert_eq!(parse_duration("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+    }
+    
+    #[test]
+    fn rejects_malformed_durations() {
+        for value in ["", "7", "d", "7y", "-7d", "7 d"] {
+            assert!(matches!(parse_duration(value), Err(ProjectManError::InvalidQuery(_))), "{:?}", value);
+        }
+    }
+    
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert!(matches!(parse_duration("99999999999999999d"), Err(ProjectManError::InvalidQuery(_))));
+        assert!(matches!(parse_duration("9999999999999999w"), Err(ProjectManError::InvalidQuery(_))));
+        assert!(Query::parse("synced:>99999999999999999d").is_err());
+    }
+    
+    #[test]
+    fn separates_qualifiers_from_text() {
+        let query = Query::parse("api owner:my-org tag:work").unwrap();
+        assert_eq!(query.text, "api");
+        assert_eq!(query.filters.len(), 2);
+        assert!(!query.needs_status());
+        
+        assert!(Query::parse("dirty:true").unwrap().needs_status());
+        // Unknown keys are plain text, e.g. an SSH URL
+        assert_eq!(Query::parse("git@host:repo").unwrap().text, "git@host:repo");
+    }
+    
+    #[test]
+    fn rejects_bad_qualifier_values() {
+        assert!(Query::parse("dirty:maybe").is_err());
+        assert!(Query::parse("owner:").is_err());
+        assert!(Query::parse("synced:soon").is_err());
+    }
+    
+    #[test]
+    fn matches_path_and_tag_qualifiers() {
+        let config = repo("github.com/my-org/api", &["work"], None);
+        
+        assert!(Query::parse("host:github.com owner:My-Org").unwrap().matches(&config, None));
+        assert!(Query::parse("tag:work").unwrap().matches(&config, None));
+        assert!(!Query::parse("!tag:work").unwrap().matches(&config, None));
+        assert!(!Query::parse("-my-org").unwrap().matches(&config, None));
+        assert!(Query::parse("!gitlab").unwrap().matches(&config, None));
+    }
+    
+    #[test]
+    fn matches_sync_age() {
+        let never = repo("github.com/o/a", &[], None);
+        let recent = repo("github.com/o/b", &[], Some(Utc::now() - Duration::hours(1)));
+        let old = repo("github.com/o/c", &[], Some(Utc::now() - Duration::days(30)));
+        
+        let stale = Query::parse("synced:>7d").unwrap();
+        assert!(stale.matches(&never, None));
+        assert!(!stale.matches(&recent, None));
+        assert!(stale.matches(&old, None));
+        
+        let fresh = Query::parse("synced:<1d").unwrap();
+        assert!(!fresh.matches(&never, None));
+        assert!(fresh.matches(&recent, None));
+        
+        assert!(Query::parse("synced:never").unwrap().matches(&never, None));
+    }
+    
+    #[test]
+    fn status_qualifiers_need_a_status() {
+        let config = repo("github.com/o/a", &[], None);
+        let dirty = RepoStatus { modified: 1, ahead: 2, ..Default::default() };
+        
+        assert!(Query::parse("dirty:true ahead:yes behind:no").unwrap().matches(&config, Some(&dirty)));
+        assert!(!Query::parse("dirty:false").unwrap().matches(&config, Some(&dirty)));
+        // Unknown status never matches, negated or not
+        assert!(!Query::parse("dirty:true").unwrap().matches(&config, None));
+        assert!(!Query::parse("!dirty:true").unwrap().matches(&config, None));
+    }
+}
\ No newline at end of file
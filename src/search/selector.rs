@@ -0,0 +1,516 @@
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::panic;
+use std::path::PathBuf;
+use std::sync::Once;
+use std::thread;
+use std::time::Duration;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use crate::config::{GlobalConfig, RepositoryConfig};
+use crate::error::Result;
+use crate::search::{FuzzySearch, MatchField, SearchResult};
+use crate::search::preview::PreviewLoader;
+
+// Header, query line and counter above the list; blank line, path and match below it
+const CHROME_ROWS: usize = 6;
+const MAX_HEIGHT: usize = 20;
+const LIST_TOP: usize = 3;
+const PREVIEW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+// Narrower terminals show the list alone
+const MIN_SPLIT_WIDTH: u16 = 80;
+
+enum Action {
+    Continue,
+    Accept,
+    Cancel,
+}
+
+/// Incremental finder over a fixed pool of candidates.
+///
+/// Typing refines the pool by re-running `FuzzySearch::search` on every keystroke. The
+/// selector draws inline below the cursor rather than taking over the screen.
+pub struct Selector<'a> {
+    search: &'a FuzzySearch,
+    pool: Vec<SearchResult>,
+    pool_repos: Vec<(String, RepositoryConfig)>,
+    query: String,
+    results: Vec<SearchResult>,
+    selected: usize,
+    offset: usize,
+    height: usize,
+    // Multi-select mode: names marked with Tab
+    multi: bool,
+    marked: HashSet<String>,
+    workspace_path: Option<PathBuf>,
+    previews: PreviewLoader,
+}
+
+impl<'a> Selector<'a> {
+    pub fn new(search: &'a FuzzySearch, candidates: Vec<SearchResult>) -> Self {
+        let pool_repos = candidates
+            .iter()
+            .map(|candidate| (candidate.name.clone(), candidate.repo_config.clone()))
+            .collect();
+        
+        Self {
+            search,
+            results: candidates.clone(),
+            pool: candidates,
+            pool_repos,
+            query: String::new(),
+            selected: 0,
+            offset: 0,
+            height: MAX_HEIGHT,
+            multi: false,
+            marked: HashSet::new(),
+            workspace_path: GlobalConfig::load().ok().map(|config| config.get_workspace_path().to_path_buf()),
+            previews: PreviewLoader::new(),
+        }
+    }
+    
+    /// Let the user mark any number of candidates instead of picking one.
+    pub fn multi(mut self) -> Self {
+        self.multi = true;
+        self
+    }
+    
+    /// Run on `out` until the user confirms or cancels; cancelling yields no candidates.
+    pub fn run(mut self, out: impl Write) -> Result<Vec<SearchResult>> {
+        let (width, terminal_rows) = terminal::size()?;
+        // With a preview the extra rows are put to use even when there are few candidates
+        let wanted = if width >= MIN_SPLIT_WIDTH { MAX_HEIGHT } else { CHROME_ROWS + self.pool.len() };
+        self.height = wanted.min(MAX_HEIGHT).min(terminal_rows as usize).max(1);
+        
+        let mut region = Region::open(out, self.height as u16)?;
+        let mut dirty = true;
+        
+        loop {
+            if dirty {
+                self.request_preview();
+                region.draw(&self.frame(), self.cursor())?;
+            }
+            
+            // Wake up periodically to pick up previews finished in the background
+            if !event::poll(PREVIEW_POLL_INTERVAL)? {
+                dirty = self.previews.poll();
+                continue;
+            }
+            
+            dirty = true;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => match self.handle_key(key) {
+                    Action::Continue => {}
+                    Action::Accept => return Ok(self.accepted()),
+                    Action::Cancel => return Ok(Vec::new()),
+                },
+                Event::Resize(..) => region.invalidate(),
+                _ => {}
+            }
+        }
+    }
+    
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        
+        match key.code {
+            KeyCode::Enter => return Action::Accept,
+            KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('c') if ctrl => return Action::Cancel,
+            
+            KeyCode::Tab if self.multi => {
+                self.toggle_mark();
+                self.move_by(1);
+            }
+            KeyCode::BackTab if self.multi => {
+                self.toggle_mark();
+                self.move_by(-1);
+            }
+            KeyCode::Char('a') if ctrl && self.multi => self.mark_all(),
+            
+            KeyCode::Up => self.move_by(-1),
+            KeyCode::Down => self.move_by(1),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_by(-1),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-(self.list_rows() as isize)),
+            KeyCode::PageDown => self.move_by(self.list_rows() as isize),
+            KeyCode::Char('b') if ctrl => self.move_by(-(self.list_rows() as isize)),
+            KeyCode::Char('f') if ctrl => self.move_by(self.list_rows() as isize),
+            KeyCode::Home => self.move_by(isize::MIN),
+            KeyCode::End => self.move_by(isize::MAX),
+            
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Char('w') if ctrl => {
+                let trimmed = self.query.trim_end().len();
+                let word_start = self.query[..trimmed].rfind(' ').map(|i| i + 1).unwrap_or(0);
+                self.query.truncate(word_start);
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refilter();
+            }
+            _ => {}
+        }
+        
+        Action::Continue
+    }
+    
+    fn toggle_mark(&mut self) {
+        if let Some(candidate) = self.results.get(self.selected) {
+            if !self.marked.remove(&candidate.name) {
+                self.marked.insert(candidate.name.clone());
+            }
+        }
+    }
+    
+    // Marks every visible result, or clears them when all are marked already
+    fn mark_all(&mut self) {
+        if self.results.iter().all(|candidate| self.marked.contains(&candidate.name)) {
+            for candidate in &self.results {
+                self.marked.remove(&candidate.name);
+            }
+        } else {
+            self.marked.extend(self.results.iter().map(|candidate| candidate.name.clone()));
+        }
+    }
+    
+    /// Marked candidates in their original order, or the highlighted one when none are marked.
+    fn accepted(&self) -> Vec<SearchResult> {
+        if self.marked.is_empty() {
+            return self.results.get(self.selected).cloned().into_iter().collect();
+        }
+        
+        self.pool.iter()
+            .filter(|candidate| self.marked.contains(&candidate.name))
+            .cloned()
+            .collect()
+    }
+    
+    fn request_preview(&mut self) {
+        let (Some(candidate), Some(workspace_path)) = (self.results.get(self.selected), &self.workspace_path) else {
+            return;
+        };
+        
+        let repo_path = workspace_path.join(&candidate.repo_config.path);
+        self.previews.request(&candidate.name, repo_path);
+    }
+    
+    fn refilter(&mut self) {
+        self.results = if self.query.is_empty() {
+            self.pool.clone()
+        } else {
+            self.search.search(&self.pool_repos, &self.query)
+        };
+        self.selected = 0;
+        self.offset = 0;
+    }
+    
+    fn move_by(&mut self, delta: isize) {
+        if self.results.is_empty() {
+            return;
+        }
+        
+        let last = self.results.len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+        
+        // Keep the selection inside the visible window
+        let rows = self.list_rows();
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + rows {
+            self.offset = self.selected + 1 - rows;
+        }
+    }
+    
+    fn list_rows(&self) -> usize {
+        self.height.saturating_sub(CHROME_ROWS).max(1)
+    }
+    
+    fn frame(&self) -> Vec<Line> {
+        let (width, _) = terminal::size().unwrap_or((80, 24));
+        let preview_column = (width >= MIN_SPLIT_WIDTH).then_some(width / 2);
+        let selected = self.results.get(self.selected);
+        
+        let mut lines = vec![
+            Line::plain(Color::Yellow, self.header()),
+            Line::plain(Color::Reset, format!("> {}", self.query)),
+            Line::plain(Color::DarkGrey, self.counter()),
+        ];
+        
+        for i in self.offset..self.offset + self.list_rows() {
+            let Some(candidate) = self.results.get(i) else {
+                lines.push(Line::default());
+                continue;
+            };
+            
+            let is_selected = i == self.selected;
+            let mut prefix = if is_selected { "➤ " } else { "  " }.to_string();
+            if self.multi {
+                prefix.push_str(if self.marked.contains(&candidate.name) { "◉ " } else { "○ " });
+            }
+            let color = if is_selected { Color::Green } else { Color::White };
+            let name_indices = match candidate.matched_field {
+                MatchField::Name => candidate.match_indices.as_slice(),
+                _ => &[],
+            };
+            lines.push(Line::highlighted(&prefix, &candidate.name, name_indices, color));
+        }
+        
+        if let (Some(column), Some(candidate)) = (preview_column, selected) {
+            let preview = match self.previews.get(&candidate.name) {
+                Some(preview) => preview.lines(&candidate.repo_config),
+                None => vec!["⏳ Loading preview...".to_string()],
+            };
+            
+            for (row, line) in lines[LIST_TOP..].iter_mut().enumerate() {
+                let text = preview.get(row).cloned().unwrap_or_default();
+                line.right = Some((column, vec![(Color::DarkGrey, "│ ".to_string()), (Color::Reset, text)]));
+            }
+        }
+        
+        // Details of the selected item, including what matched when it isn't the name
+        lines.push(Line::default());
+        if let Some(candidate) = selected {
+            let path_indices = match candidate.matched_field {
+                MatchField::Path => candidate.match_indices.as_slice(),
+                _ => &[],
+            };
+            lines.push(Line::highlighted("📁 ", &candidate.repo_config.path, path_indices, Color::DarkGrey));
+            
+            if matches!(candidate.matched_field, MatchField::Alias | MatchField::Tag | MatchField::Url) {
+                let label = format!("🔎 {}: ", candidate.matched_field.label());
+                lines.push(Line::highlighted(&label, &candidate.matched_text, &candidate.match_indices, Color::DarkGrey));
+            }
+        }
+        
+        lines
+    }
+    
+    fn header(&self) -> &'static str {
+        if self.multi {
+            "📋 Select repositories (type to filter, Tab to mark, Ctrl-A to mark all, Enter to confirm, Esc to cancel)"
+        } else {
+            "📋 Select a repository (type to filter, ↑/↓ or Ctrl-N/P to move, Enter to select, Esc to cancel)"
+        }
+    }
+    
+    fn counter(&self) -> String {
+        let counter = format!("  {}/{}", self.results.len(), self.pool.len());
+        if self.multi {
+            format!("{} · {} selected", counter, self.marked.len())
+        } else {
+            counter
+        }
+    }
+    
+    /// Position of the text cursor within the region: the end of the query line.
+    fn cursor(&self) -> (u16, u16) {
+        (2 + display_width(&self.query) as u16, 1)
+    }
+}
+
+/// One row of the selector as coloured runs of text, with an optional second column.
+#[derive(Clone, Default, PartialEq)]
+struct Line {
+    spans: Vec<(Color, String)>,
+    right: Option<(u16, Vec<(Color, String)>)>,
+}
+
+impl Line {
+    fn plain(color: Color, text: impl Into<String>) -> Self {
+        Self { spans: vec![(color, text.into())], right: None }
+    }
+    
+    /// `prefix` and `text` in `color`, with the characters at `indices` emphasised.
+    fn highlighted(prefix: &str, text: &str, indices: &[usize], color: Color) -> Self {
+        let mut spans = vec![(color, prefix.to_string())];
+        
+        for (i, ch) in text.chars().enumerate() {
+            let ch_color = if indices.contains(&i) { Color::Cyan } else { color };
+            match spans.last_mut() {
+                Some((last_color, run)) if *last_color == ch_color => run.push(ch),
+                _ => spans.push((ch_color, ch.to_string())),
+            }
+        }
+        
+        Self { spans, right: None }
+    }
+}
+
+/// The rows just below the cursor that the selector draws into.
+///
+/// Raw mode is entered on open and left on drop, together with clearing the rows, so
+/// the terminal is restored however the selector exits. The cursor is only ever moved
+/// relative to where it started: asking the terminal for its position would go through
+/// stdout, which may be captured by the shell.
+struct Region<W: Write> {
+    out: W,
+    height: u16,
+    // Cursor row relative to the top of the region
+    row: u16,
+    drawn: Vec<Line>,
+}
+
+impl<W: Write> Region<W> {
+    fn open(out: W, height: u16) -> Result<Self> {
+        install_panic_hook();
+        terminal::enable_raw_mode()?;
+        
+        // From here on Drop takes care of leaving raw mode
+        let mut region = Self { out, height, row: 0, drawn: Vec::new() };
+        
+        // Line feeds scroll existing output up when the region doesn't fit below the cursor
+        queue!(region.out, Print("\r"), Print("\n".repeat(height as usize - 1)))?;
+        region.row = height - 1;
+        region.move_to(0, 0)?;
+        
+        Ok(region)
+    }
+    
+    fn move_to(&mut self, row: u16, column: u16) -> Result<()> {
+        // A zero count still moves one row in most terminals
+        if row > self.row {
+            queue!(self.out, cursor::MoveDown(row - self.row))?;
+        } else if row < self.row {
+            queue!(self.out, cursor::MoveUp(self.row - row))?;
+        }
+        queue!(self.out, cursor::MoveToColumn(column))?;
+        self.row = row;
+        Ok(())
+    }
+    
+    /// Redraw the rows that differ from what is on screen.
+    fn draw(&mut self, frame: &[Line], cursor: (u16, u16)) -> Result<()> {
+        let (width, _) = terminal::size().unwrap_or((80, 24));
+        queue!(self.out, cursor::Hide)?;
+        
+        for row in 0..self.height as usize {
+            let line = frame.get(row).cloned().unwrap_or_default();
+            if self.drawn.get(row) == Some(&line) {
+                continue;
+            }
+            
+            self.move_to(row as u16, 0)?;
+            queue!(self.out, terminal::Clear(ClearType::CurrentLine))?;
+            
+            let left_width = line.right.as_ref().map_or(width, |(column, _)| *column);
+            write_spans(&mut self.out, &line.spans, left_width.saturating_sub(1) as usize)?;
+            
+            if let Some((column, spans)) = &line.right {
+                queue!(self.out, cursor::MoveToColumn(*column))?;
+                write_spans(&mut self.out, spans, width.saturating_sub(*column + 1) as usize)?;
+            }
+        }
+        
+        self.drawn = frame.iter().take(self.height as usize).cloned().collect();
+        self.drawn.resize(self.height as usize, Line::default());
+        
+        self.move_to(cursor.1, cursor.0)?;
+        queue!(self.out, cursor::Show)?;
+        self.out.flush()?;
+        Ok(())
+    }
+    
+    /// Forget what is on screen so the next draw repaints every row, e.g. after a resize.
+    fn invalidate(&mut self) {
+        self.drawn.clear();
+    }
+}
+
+impl<W: Write> Drop for Region<W> {
+    fn drop(&mut self) {
+        // Keep a panic message visible instead of clearing over it
+        let _ = if thread::panicking() {
+            self.move_to(self.height - 1, 0).and_then(|_| Ok(queue!(self.out, Print("\r\n"))?))
+        } else {
+            self.move_to(0, 0).and_then(|_| Ok(queue!(self.out, terminal::Clear(ClearType::FromCursorDown))?))
+        };
+        let _ = queue!(self.out, ResetColor, cursor::Show);
+        let _ = self.out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Where the selector can be drawn without ending up in captured output: the terminal
+/// itself when stdout is redirected, or stderr as a last resort.
+pub fn open_terminal() -> Option<Box<dyn Write>> {
+    if io::stdin().is_terminal() && io::stdout().is_terminal() {
+        return Some(Box::new(io::stdout()));
+    }
+    
+    if let Ok(tty) = OpenOptions::new().read(true).write(true).open("/dev/tty") {
+        return Some(Box::new(tty));
+    }
+    
+    if io::stdin().is_terminal() && io::stderr().is_terminal() {
+        return Some(Box::new(io::stderr()));
+    }
+    
+    None
+}
+
+/// Leave raw mode before the panic message is printed, so it isn't mangled.
+fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    
+    INSTALLED.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = terminal::disable_raw_mode();
+            previous(info);
+        }));
+    });
+}
+
+/// Write `spans`, cutting them off after `max_width` terminal columns.
+fn write_spans(out: &mut impl Write, spans: &[(Color, String)], max_width: usize) -> Result<()> {
+    let mut used = 0;
+    
+    for (color, text) in spans {
+        let mut visible = String::new();
+        for ch in text.chars() {
+            used += char_width(ch);
+            if used > max_width {
+                break;
+            }
+            visible.push(ch);
+        }
+        
+        queue!(out, SetForegroundColor(*color), Print(visible))?;
+        if used > max_width {
+            break;
+        }
+    }
+    
+    queue!(out, ResetColor)?;
+    Ok(())
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+// Rough terminal cell width: emoji and CJK take two cells, combining marks none
+fn char_width(ch: char) -> usize {
+    match ch as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x1F300..=0x1FAFF
+        | 0x23E9..=0x23F3 | 0x2705 | 0x274C | 0x2753..=0x2755 => 2,
+        _ => 1,
+    }
+}
\ No newline at end of file
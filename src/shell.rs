@@ -0,0 +1,346 @@
+use clap::ValueEnum;
+use clap_complete::CompletionCandidate;
+use clap_complete::env::Shells;
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::path::Path;
+use crate::config::{CONFIG_KEYS, WorkspaceRegistry};
+use crate::error::{ProjectManError, Result};
+
+/// Environment variable naming the file `--output-cd` writes the target directory to.
+///
+/// The shell wrapper creates the file, runs `p` with this set, and `cd`s to the file's
+/// contents afterwards. The path is written as raw bytes without a trailing newline, so
+/// any directory name survives, and stdout is left to the human-readable output.
+pub const CD_FILE_ENV: &str = "P_CD_FILE";
+
+/// Environment variable that switches the binary into answering shell completion requests.
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+/// Pass `path` to the shell wrapper so it can change into it.
+pub fn hand_off_directory(path: &Path) -> Result<()> {
+    let cd_file = std::env::var_os(CD_FILE_ENV).ok_or_else(|| ProjectManError::ShellIntegration(format!(
+        "--output-cd is used by the shell integration and needs {} set to the file to write the directory to",
+        CD_FILE_ENV
+    )))?;
+    
+    std::fs::write(cd_file, path.as_os_str().as_encoded_bytes())?;
+    Ok(())
+}
+
+/// Shells `p shell-init` can generate the wrapper function for.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+}
+
+/// The `p` wrapper function for `shell`.
+///
+/// `binary` is the path of the real executable and `cd_commands` the subcommands that
+/// accept `--output-cd`; every other command is passed through untouched. The subcommand
+/// is the first word that isn't a global option, so the wrapper also needs the global
+/// `value_options` (e.g. `--workspace`) to skip the value that follows them.
+///
+/// `--output-cd` goes right after the subcommand, where a `--` later on can't turn it
+/// into a positional argument.
+pub fn init_script(shell: Shell, binary: &str, cd_commands: &[String], value_options: &[String]) -> String {
+    let mut script = match shell {
+        Shell::Bash | Shell::Zsh => posix_script(shell, binary, cd_commands, value_options),
+        Shell::Fish => fish_script(binary, cd_commands, value_options),
+        Shell::Nushell => nushell_script(binary, cd_commands, value_options),
+    };
+    script.push_str(&completion_script(shell, binary));
+    script
+}
+
+/// Registration for completions, which call back into `binary` with `COMPLETE` set.
+///
+/// Nushell has no dynamic completion support in `clap_complete`, so it gets none.
+fn completion_script(shell: Shell, binary: &str) -> String {
+    let name = match shell {
+        Shell::Bash => "bash",
+        Shell::Zsh => "zsh",
+        Shell::Fish => "fish",
+        Shell::Nushell => return String::new(),
+    };
+    let shells = Shells::builtins();
+    let Some(completer) = shells.completer(name) else {
+        return String::new();
+    };
+    
+    let mut script = Vec::new();
+    if completer.write_registration(COMPLETE_ENV, "p", "p", binary, &mut script).is_err() {
+        return String::new();
+    }
+    format!("\n{}", String::from_utf8_lossy(&script))
+}
+
+/// The `--workspace` given on a command line being completed, so completions come from
+/// that workspace rather than the active one.
+///
+/// `args` are the binary's own arguments, with the words of the line after the first `--`;
+/// `cursor` is the index of the word being completed, the last word when unknown.
+pub fn completion_workspace(args: impl IntoIterator<Item = String>, cursor: Option<usize>) -> Option<String> {
+    let mut words: Vec<String> = args.into_iter().skip_while(|arg| arg != "--").skip(1).collect();
+    words.truncate(cursor.unwrap_or(words.len().saturating_sub(1)));
+    
+    let mut workspace = None;
+    let mut words = words.into_iter();
+    while let Some(word) = words.next() {
+        if word == "--" {
+            break;
+        } else if word == "--workspace" {
+            workspace = words.next();
+        } else if let Some(name) = word.strip_prefix("--workspace=") {
+            workspace = Some(name.to_string());
+        }
+    }
+    workspace
+}
+
+/// Complete a repository query from registered names, paths, aliases and tags.
+pub fn complete_repository(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Ok(registry) = WorkspaceRegistry::load_from_workspace() else {
+        return Vec::new();
+    };
+    let current = current.to_string_lossy();
+    
+    let mut candidates = Vec::new();
+    let mut tags = BTreeSet::new();
+    for (name, repo_config) in registry.list_repositories() {
+        candidates.push(CompletionCandidate::new(name).help(Some(repo_config.path.clone().into())));
+        candidates.push(CompletionCandidate::new(&repo_config.path).help(Some(name.clone().into())));
+        for alias in &repo_config.aliases {
+            candidates.push(CompletionCandidate::new(alias).help(Some(format!("alias of {}", name).into())));
+        }
+        tags.extend(repo_config.tags.iter().map(|tag| format!("tag:{}", tag)));
+    }
+    candidates.extend(tags.into_iter().map(CompletionCandidate::new));
+    
+    candidates.retain(|candidate| candidate.get_value().to_string_lossy().starts_with(current.as_ref()));
+    candidates
+}
+
+/// Complete a tag used anywhere in the workspace.
+pub fn complete_tag(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Ok(registry) = WorkspaceRegistry::load_from_workspace() else {
+        return Vec::new();
+    };
+    let current = current.to_string_lossy();
+    
+    let tags: BTreeSet<&String> = registry.list_repositories()
+        .into_iter()
+        .flat_map(|(_, repo_config)| &repo_config.tags)
+        .filter(|tag| tag.starts_with(current.as_ref()))
+        .collect();
+    tags.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Complete a `p config get/set` key.
+pub fn complete_config_key(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    CONFIG_KEYS
+        .iter()
+        .filter(|key| key.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+fn posix_script(shell: Shell, binary: &str, cd_commands: &[String], value_options: &[String]) -> String {
+    let binary = format!("'{}'", binary.replace('\'', r"'\''"));
+    let name = if matches!(shell, Shell::Zsh) { "zsh" } else { "bash" };
+    
+    format!(r#"# Project Man shell integration, generated by `p shell-init {name}`
+# Add to your shell config: eval "$(p-bin shell-init {name})"
+
+p() {{
+    # Find the subcommand behind any global options
+    local word command= index=0 skip_value=false
+    for word in "$@"; do
+        index=$((index + 1))
+        if $skip_value; then
+            skip_value=false
+            continue
+        fi
+        case "$word" in
+            {value_options}) skip_value=true ;;
+            --) break ;;
+            -*) ;;
+            *) command=$word; break ;;
+        esac
+    done
+    
+    case "$command" in
+        {commands})
+            local cd_file cd_target exit_code
+            cd_file=$(mktemp) || return 1
+            P_CD_FILE="$cd_file" {binary} "${{@:1:$index}}" --output-cd "${{@:$((index + 1))}}"
+            exit_code=$?
+            if [ $exit_code -eq 0 ] && [ -s "$cd_file" ]; then
+                # The trailing x keeps command substitution from eating newlines in the path
+                cd_target=$(cat "$cd_file"; printf x)
+                cd_target=${{cd_target%x}}
+                if cd -- "$cd_target"; then
+                    echo "📁 Changed to: $PWD"
+                else
+                    exit_code=1
+                fi
+            fi
+            rm -f "$cd_file"
+            return $exit_code
+            ;;
+        *)
+            {binary} "$@"
+            ;;
+    esac
+}}
+"#, commands = cd_commands.join("|"), value_options = value_options.join("|"))
+}
+
+fn fish_script(binary: &str, cd_commands: &[String], value_options: &[String]) -> String {
+    let binary = format!("'{}'", binary.replace('\\', r"\\").replace('\'', r"\'"));
+    
+    format!(r#"# Project Man shell integration, generated by `p shell-init fish`
+# Add to ~/.config/fish/config.fish: p-bin shell-init fish | source
+
+function p
+    # Find the subcommand behind any global options
+    set -l command
+    set -l index 0
+    set -l skip_value 0
+    for word in $argv
+        set index (math $index + 1)
+        if test $skip_value -eq 1
+            set skip_value 0
+            continue
+        end
+        switch $word
+            case {value_options}
+                set skip_value 1
+            case --
+                break
+            case '-*'
+            case '*'
+                set command $word
+                break
+        end
+    end
+    
+    switch "$command"
+        case {commands}
+            set -l cd_file (mktemp); or return 1
+            env P_CD_FILE=$cd_file {binary} $argv[1..$index] --output-cd $argv[(math $index + 1)..-1]
+            set -l exit_code $status
+            if test $exit_code -eq 0; and test -s $cd_file
+                set -l cd_target (string collect --no-trim-newlines < $cd_file)
+                if cd $cd_target
+                    echo "📁 Changed to: $PWD"
+                else
+                    set exit_code 1
+                end
+            end
+            rm -f $cd_file
+            return $exit_code
+        case '*'
+            {binary} $argv
+    end
+end
+"#, commands = cd_commands.join(" "), value_options = value_options.join(" "))
+}
+
+fn nushell_script(binary: &str, cd_commands: &[String], value_options: &[String]) -> String {
+    // A raw string, so the path needs no escaping
+    let binary = format!("r#'{}'#", binary);
+    let commands: Vec<String> = cd_commands.iter().map(|command| format!("'{}'", command)).collect();
+    let value_options: Vec<String> = value_options.iter().map(|option| format!("'{}'", option)).collect();
+    
+    format!(r#"# Project Man shell integration, generated by `p shell-init nushell`
+# Save it and source it from config.nu:
+#   p-bin shell-init nushell | save -f ~/.config/nushell/p.nu
+#   source ~/.config/nushell/p.nu
+
+def --env --wrapped p [...args] {{
+    let binary = {binary}
+    
+    # Find the subcommand behind any global options
+    mut command = ''
+    mut index = 0
+    mut skip_value = false
+    for word in $args {{
+        $index += 1
+        if $skip_value {{
+            $skip_value = false
+        }} else if $word in [{value_options}] {{
+            $skip_value = true
+        }} else if $word == '--' {{
+            break
+        }} else if not ($word | str starts-with '-') {{
+            $command = $word
+            break
+        }}
+    }}
+    
+    if $command in [{commands}] {{
+        let cd_file = (mktemp -t)
+        let before = ($args | first $index)
+        let after = ($args | skip $index)
+        with-env {{ P_CD_FILE: $cd_file }} {{ ^$binary ...$before --output-cd ...$after }}
+        let cd_target = (open --raw $cd_file)
+        rm -f $cd_file
+        if ($cd_target | is-not-empty) {{
+            cd $cd_target
+            print $"📁 Changed to: ($env.PWD)"
+        }}
+    }} else {{
+        ^$binary ...$args
+    }}
+}}
+"#, commands = commands.join(", "), value_options = value_options.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn line(words: &str) -> Vec<String> {
+        std::iter::once("p-bin").chain(words.split(' ')).map(str::to_string).collect()
+    }
+    
+    #[test]
+    fn finds_workspace_before_the_cursor() {
+        assert_eq!(completion_workspace(line("-- p --workspace oss go "), None), Some("oss".to_string()));
+        assert_eq!(completion_workspace(line("-- p --workspace=oss go a"), None), Some("oss".to_string()));
+        assert_eq!(completion_workspace(line("-- p go --workspace oss "), Some(5)), Some("oss".to_string()));
+    }
+    
+    #[test]
+    fn ignores_workspace_being_typed_or_after_the_cursor() {
+        assert_eq!(completion_workspace(line("-- p go "), None), None);
+        // The value under the cursor is incomplete
+        assert_eq!(completion_workspace(line("-- p --workspace os"), None), None);
+        assert_eq!(completion_workspace(line("-- p go  --workspace oss"), Some(2)), None);
+        // After `--` it's a positional argument
+        assert_eq!(completion_workspace(line("-- p go -- --workspace oss "), None), None);
+    }
+    
+    #[test]
+    fn wrapper_skips_global_options_and_places_output_cd_after_the_subcommand() {
+        let commands = vec!["go".to_string(), "add".to_string()];
+        let options = vec!["--workspace".to_string(), "--select".to_string()];
+        
+        let bash = init_script(Shell::Bash, "/bin/p-bin", &commands, &options);
+        assert!(bash.contains("--workspace|--select) skip_value=true"));
+        assert!(bash.contains(r#""${@:1:$index}" --output-cd "${@:$((index + 1))}""#));
+        
+        let fish = fish_script("/bin/p-bin", &commands, &options);
+        assert!(fish.contains("case --workspace --select"));
+        assert!(fish.contains("case go add"));
+        
+        let nushell = nushell_script("/bin/p-bin", &commands, &options);
+        assert!(nushell.contains("$word in ['--workspace', '--select']"));
+        assert!(nushell.contains("...$before --output-cd ...$after"));
+    }
+}
\ No newline at end of file